@@ -0,0 +1,186 @@
+//! Read-only endpoint describing the configuration a process is running with.
+//!
+//! ```ignore
+//! let redaction = Redaction::new().key("**.password").secrets::<DatabaseConfig>();
+//!
+//! let app = Router::new()
+//!     .route("/", get(handler))
+//!     .nest("/debug/config", axum_config::debug_router(config.clone(), redaction))
+//!     .layer(Extension(config));
+//! ```
+//!
+//! `GET /` on the nested router returns the sources, the load time (Unix seconds),
+//! the active profile and the effective configuration with redactions applied.
+
+#[cfg(feature = "reload")]
+use crate::ReloadableConfig;
+use crate::{Config, ConfigSourceInfo, Redaction};
+use axum::{Json, Router, routing::get};
+use serde::Serialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Serialize)]
+struct DebugInfo {
+    loaded_at: Option<u64>,
+    profile: Option<String>,
+    sources: Vec<ConfigSourceInfo>,
+    config: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reload: Option<ReloadInfo>,
+}
+
+#[derive(Serialize)]
+struct ReloadInfo {
+    reloads: u64,
+    last_attempt: Option<u64>,
+    last_error: Option<String>,
+}
+
+impl DebugInfo {
+    fn new(config: &Config, redaction: &Redaction) -> Self {
+        Self {
+            loaded_at: config.loaded_at().and_then(unix_seconds),
+            profile: config.profile().map(str::to_string),
+            sources: config.sources().to_vec(),
+            config: config.to_json_value(redaction),
+            reload: None,
+        }
+    }
+}
+
+/// Router serving the sources and redacted effective configuration of `config`.
+pub fn debug_router<S>(config: Config, redaction: Redaction) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new().route(
+        "/",
+        get(move || async move { Json(DebugInfo::new(&config, &redaction)) }),
+    )
+}
+
+#[cfg(feature = "reload")]
+/// Like [`debug_router`], for the currently active configuration of a
+/// [`ReloadableConfig`], including its reload status.
+pub fn reloadable_debug_router<S>(config: ReloadableConfig, redaction: Redaction) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new().route(
+        "/",
+        get(move || async move {
+            let status = config.status();
+            let mut info = DebugInfo::new(&config.current(), &redaction);
+
+            info.reload = Some(ReloadInfo {
+                reloads: status.reloads,
+                last_attempt: status.last_attempt.and_then(unix_seconds),
+                last_error: status.last_error_summary,
+            });
+
+            Json(info)
+        }),
+    )
+}
+
+fn unix_seconds(time: SystemTime) -> Option<u64> {
+    time.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request};
+    use tower::ServiceExt;
+
+    async fn fetch(router: Router) -> serde_json::Value {
+        let response = router
+            .oneshot(
+                Request::get("/")
+                    .body(Body::empty())
+                    .expect("valid request"),
+            )
+            .await
+            .expect("infallible");
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("failed to read body");
+
+        serde_json::from_slice(&body).expect("valid JSON")
+    }
+
+    #[tokio::test]
+    async fn test_debug_router_redacts_config() {
+        let config = Config::builder()
+            .add_toml_str("[db]\nuser = \"admin\"\npassword = \"hunter2\"")
+            .build()
+            .expect("failed to build config");
+
+        let info = fetch(debug_router(config, Redaction::new().key("*.password"))).await;
+
+        assert_eq!(info["config"]["db"]["user"], "admin");
+        assert_eq!(info["config"]["db"]["password"], "***");
+        assert_eq!(info["sources"][0]["kind"], "toml_string");
+        assert!(info["loaded_at"].is_u64());
+        assert!(info.get("reload").is_none());
+    }
+
+    #[cfg(feature = "reload")]
+    #[tokio::test]
+    async fn test_reloadable_debug_router_reports_status() {
+        let config = Config::builder()
+            .add_toml_str("[db]\nuser = \"admin\"")
+            .build_reloadable()
+            .expect("failed to build config");
+
+        config.reload().expect("failed to reload");
+
+        let info = fetch(reloadable_debug_router(config, Redaction::default())).await;
+
+        assert_eq!(info["reload"]["reloads"], 1);
+        assert!(info["reload"]["last_error"].is_null());
+    }
+
+    #[cfg(feature = "reload")]
+    #[tokio::test]
+    async fn test_reloadable_debug_router_omits_values_from_errors() {
+        use crate::ConfigItem;
+
+        #[derive(Debug, Clone, serde::Deserialize)]
+        #[allow(dead_code)]
+        struct DbConfig {
+            port: u16,
+        }
+
+        impl ConfigItem for DbConfig {
+            fn key() -> &'static str {
+                "db"
+            }
+        }
+
+        let config = Config::builder()
+            .add_toml_str("[db]\nport = 5432")
+            .add_env_prefix("DEBUGRELOAD", "__")
+            .require::<DbConfig>()
+            .build_reloadable()
+            .expect("failed to build config");
+
+        unsafe { std::env::set_var("DEBUGRELOAD__DB__PORT", "hunter2") };
+        assert!(config.reload().is_err());
+        assert!(
+            config
+                .status()
+                .last_error
+                .is_some_and(|e| e.contains("hunter2"))
+        );
+
+        let info = fetch(reloadable_debug_router(config, Redaction::default())).await;
+        let last_error = info["reload"]["last_error"]
+            .as_str()
+            .expect("missing error");
+
+        assert!(last_error.contains("'db' at 'port'"));
+        assert!(!last_error.contains("hunter2"));
+    }
+}
@@ -5,12 +5,16 @@ use axum::{
 };
 
 use serde::Serialize;
+use std::{fmt, sync::Arc};
+use thisconfig::ConfigError;
 
 #[derive(Serialize)]
 pub struct ErrorResponse {
     code: u16,
     success: bool,
     message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
 }
 
 impl ErrorResponse {
@@ -19,6 +23,7 @@ impl ErrorResponse {
             code: 500,
             success: false,
             message: "Internal Server Error".to_string(),
+            detail: None,
         }
     }
 
@@ -27,6 +32,7 @@ impl ErrorResponse {
             code: 400,
             success: false,
             message: "Bad Request".to_string(),
+            detail: None,
         }
     }
 }
@@ -38,9 +44,127 @@ impl IntoResponse for ErrorResponse {
     }
 }
 
+/// Reason a config extractor rejected a request.
+#[derive(Debug)]
+pub enum RejectionReason {
+    /// No `Config` was found in the request extensions.
+    MissingConfig,
+    /// The section could not be loaded or validated.
+    Section(Box<ConfigError>),
+}
+
+/// Rejection returned by every config extractor.
+///
+/// By default it renders a 500 JSON body `{code, success, message}`; debug builds also
+/// include a `detail` field naming the section key and, when known, the offending field.
+/// The underlying error is not included, since serde messages can quote the rejected value.
+/// Insert a [`RejectionHandler`] as a request extension to render your own response instead.
+#[derive(Debug)]
+pub struct ConfigRejection {
+    key: &'static str,
+    reason: RejectionReason,
+    handler: Option<RejectionHandler>,
+}
+
+impl ConfigRejection {
+    pub(crate) fn new(
+        key: &'static str,
+        reason: RejectionReason,
+        handler: Option<RejectionHandler>,
+    ) -> Self {
+        Self {
+            key,
+            reason,
+            handler,
+        }
+    }
+
+    /// Key of the section the extractor was looking for.
+    pub fn key(&self) -> &'static str {
+        self.key
+    }
+
+    pub fn reason(&self) -> &RejectionReason {
+        &self.reason
+    }
+
+    /// Describes the rejection by section key and field path only, without values.
+    fn detail(&self) -> String {
+        match &self.reason {
+            RejectionReason::MissingConfig => {
+                format!(
+                    "Configuration not available while extracting '{}'",
+                    self.key
+                )
+            }
+            RejectionReason::Section(e) => match e.as_ref() {
+                ConfigError::KeyNotFound { .. } | ConfigError::SectionError { .. } => e.summary(),
+                _ => format!("Invalid configuration for '{}'", self.key),
+            },
+        }
+    }
+}
+
+impl fmt::Display for ConfigRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.reason {
+            RejectionReason::MissingConfig => write!(
+                f,
+                "Configuration not available while extracting '{}'",
+                self.key
+            ),
+            RejectionReason::Section(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl IntoResponse for ConfigRejection {
+    fn into_response(self) -> Response {
+        if let Some(handler) = &self.handler {
+            return (handler.0)(&self);
+        }
+
+        let mut response = ErrorResponse::internal_server_error();
+
+        if cfg!(debug_assertions) {
+            response.detail = Some(self.detail());
+        }
+
+        response.into_response()
+    }
+}
+
+/// Custom renderer for [`ConfigRejection`], registered as a request extension.
+///
+/// ```ignore
+/// let app = Router::new()
+///     .route("/", get(handler))
+///     .layer(Extension(config))
+///     .layer(Extension(RejectionHandler::new(|rejection| {
+///         MyApiError::config(rejection.key()).into_response()
+///     })));
+/// ```
+#[derive(Clone)]
+pub struct RejectionHandler(Arc<dyn Fn(&ConfigRejection) -> Response + Send + Sync>);
+
+impl RejectionHandler {
+    pub fn new<F>(handler: F) -> Self
+    where
+        F: Fn(&ConfigRejection) -> Response + Send + Sync + 'static,
+    {
+        Self(Arc::new(handler))
+    }
+}
+
+impl fmt::Debug for RejectionHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RejectionHandler")
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::error::ErrorResponse;
+    use crate::error::{ConfigRejection, ErrorResponse, RejectionHandler, RejectionReason};
     use axum::{http::StatusCode, response::IntoResponse};
 
     #[test]
@@ -49,4 +173,41 @@ mod tests {
         let response = error.into_response();
         assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
     }
+
+    #[test]
+    fn test_rejection_default_response() {
+        let rejection = ConfigRejection::new("server", RejectionReason::MissingConfig, None);
+        let response = rejection.into_response();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn test_rejection_detail_omits_values() {
+        let error = thisconfig::ConfigError::SectionError {
+            key: "db".to_string(),
+            field: Some("db.password".to_string()),
+            expected: None,
+            message: "invalid type: string \"hunter2\", expected u32".to_string(),
+            origin: None,
+        };
+
+        let rejection = ConfigRejection::new("db", RejectionReason::Section(Box::new(error)), None);
+
+        assert_eq!(
+            rejection.detail(),
+            "Invalid configuration for 'db' at 'db.password'"
+        );
+    }
+
+    #[test]
+    fn test_rejection_custom_handler() {
+        let handler = RejectionHandler::new(|rejection| {
+            (StatusCode::SERVICE_UNAVAILABLE, rejection.key().to_string()).into_response()
+        });
+
+        let rejection =
+            ConfigRejection::new("server", RejectionReason::MissingConfig, Some(handler));
+        let response = rejection.into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
 }
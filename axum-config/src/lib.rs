@@ -1,10 +1,21 @@
 mod error;
 
+#[cfg(feature = "debug-router")]
+pub mod debug;
+pub mod state;
+
 use axum::{extract::FromRequestParts, http::request::Parts};
-use error::ErrorResponse;
+use std::sync::Arc;
 
+pub use error::{ConfigRejection, ErrorResponse, RejectionHandler, RejectionReason};
 pub use thisconfig::*;
 
+#[cfg(feature = "debug-router")]
+pub use debug::debug_router;
+
+#[cfg(all(feature = "debug-router", feature = "reload"))]
+pub use debug::reloadable_debug_router;
+
 #[cfg(feature = "byte-unit")]
 pub use thisconfig::ByteConfig;
 
@@ -18,20 +29,11 @@ where
     T: ConfigItem,
     S: Send + Sync,
 {
-    type Rejection = ErrorResponse;
+    type Rejection = ConfigRejection;
 
     async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
-        let Some(config) = parts.extensions.get::<Config>() else {
-            tracing::error!("Configuration extension not found in request parts");
-            return Err(ErrorResponse::internal_server_error());
-        };
-
-        let Some(item) = config.get::<T>() else {
-            tracing::error!("Configuration item '{}' not found", T::key());
-            return Err(ErrorResponse::internal_server_error());
-        };
-
-        Ok(ExtractConfig(item))
+        let config = extension_config::<T>(parts)?;
+        extract(config, parts).map(ExtractConfig)
     }
 }
 
@@ -42,17 +44,27 @@ where
     T: ConfigItem,
     S: Send + Sync,
 {
-    type Rejection = ErrorResponse;
+    type Rejection = ConfigRejection;
 
     async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
-        let Some(config) = parts.extensions.get::<Config>() else {
-            tracing::error!("Configuration extension not found in request parts");
-            return Err(ErrorResponse::internal_server_error());
-        };
+        let config = extension_config::<T>(parts)?;
+        Ok(ExtractOptionalConfig(extract_optional(config)))
+    }
+}
 
-        let item = config.get::<T>();
+/// Extracts a config section shared behind an `Arc`, without cloning it per request.
+pub struct ExtractSharedConfig<T>(pub Arc<T>);
+
+impl<S, T> FromRequestParts<S> for ExtractSharedConfig<T>
+where
+    T: ConfigItem,
+    S: Send + Sync,
+{
+    type Rejection = ConfigRejection;
 
-        Ok(ExtractOptionalConfig(item))
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        let config = extension_config::<T>(parts)?;
+        extract_shared(config, parts).map(ExtractSharedConfig)
     }
 }
 
@@ -68,23 +80,65 @@ where
     T: ConfigItem + Validate,
     S: Send + Sync,
 {
-    type Rejection = ErrorResponse;
+    type Rejection = ConfigRejection;
 
     async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
-        let Some(config) = parts.extensions.get::<Config>() else {
-            tracing::error!("Configuration extension not found in request parts");
-            return Err(ErrorResponse::internal_server_error());
-        };
+        let config = extension_config::<T>(parts)?;
+        extract_validated(config, parts).map(ExtractValidatedConfig)
+    }
+}
 
-        let item = config.get_validated::<T>().map_err(|e| {
-            tracing::error!("Configuration validation failed for '{}': {e}", T::key());
-            ErrorResponse::internal_server_error()
-        })?;
+fn extension_config<T: ConfigItem>(parts: &Parts) -> Result<&Config, ConfigRejection> {
+    parts.extensions.get::<Config>().ok_or_else(|| {
+        tracing::error!("Configuration extension not found in request parts");
+        rejection::<T>(RejectionReason::MissingConfig, parts)
+    })
+}
+
+fn rejection<T: ConfigItem>(reason: RejectionReason, parts: &Parts) -> ConfigRejection {
+    let handler = parts.extensions.get::<RejectionHandler>().cloned();
+    ConfigRejection::new(T::key(), reason, handler)
+}
 
-        Ok(ExtractValidatedConfig(item))
+fn extract<T: ConfigItem>(config: &Config, parts: &Parts) -> Result<T, ConfigRejection> {
+    config.try_get::<T>().map_err(|e| {
+        tracing::error!("Failed to extract configuration item '{}': {e}", T::key());
+        rejection::<T>(RejectionReason::Section(Box::new(e)), parts)
+    })
+}
+
+fn extract_shared<T: ConfigItem>(
+    config: &Config,
+    parts: &Parts,
+) -> Result<Arc<T>, ConfigRejection> {
+    config.try_get_shared::<T>().map_err(|e| {
+        tracing::error!("Failed to extract configuration item '{}': {e}", T::key());
+        rejection::<T>(RejectionReason::Section(Box::new(e)), parts)
+    })
+}
+
+fn extract_optional<T: ConfigItem>(config: &Config) -> Option<T> {
+    match config.try_get::<T>() {
+        Ok(item) => Some(item),
+        Err(ConfigError::KeyNotFound { .. }) => None,
+        Err(e) => {
+            tracing::warn!("Ignoring invalid configuration item '{}': {e}", T::key());
+            None
+        }
     }
 }
 
+#[cfg(feature = "validation")]
+fn extract_validated<T: ConfigItem + Validate>(
+    config: &Config,
+    parts: &Parts,
+) -> Result<T, ConfigRejection> {
+    config.get_validated::<T>().map_err(|e| {
+        tracing::error!("Configuration validation failed for '{}': {e}", T::key());
+        rejection::<T>(RejectionReason::Section(Box::new(e)), parts)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,156 @@
+//! Extractors that read [`Config`] from the router state instead of request extensions.
+//!
+//! They require `Config: FromRef<S>`, so a router without the configuration in its
+//! state fails to compile instead of failing at request time.
+//!
+//! ```ignore
+//! use axum_config::state::ExtractConfig;
+//!
+//! let app = Router::new()
+//!     .route("/", get(handler))
+//!     .with_state(config);
+//! ```
+
+use crate::{ConfigItem, ConfigRejection};
+use axum::{
+    extract::{FromRef, FromRequestParts},
+    http::request::Parts,
+};
+use std::sync::Arc;
+use thisconfig::Config;
+
+#[cfg(feature = "validation")]
+use validator::Validate;
+
+pub struct ExtractConfig<T>(pub T);
+
+impl<S, T> FromRequestParts<S> for ExtractConfig<T>
+where
+    T: ConfigItem,
+    S: Send + Sync,
+    Config: FromRef<S>,
+{
+    type Rejection = ConfigRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        crate::extract(&Config::from_ref(state), parts).map(ExtractConfig)
+    }
+}
+
+pub struct ExtractOptionalConfig<T>(pub Option<T>);
+
+impl<S, T> FromRequestParts<S> for ExtractOptionalConfig<T>
+where
+    T: ConfigItem,
+    S: Send + Sync,
+    Config: FromRef<S>,
+{
+    type Rejection = ConfigRejection;
+
+    async fn from_request_parts(_: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        Ok(ExtractOptionalConfig(crate::extract_optional(
+            &Config::from_ref(state),
+        )))
+    }
+}
+
+/// Extracts a config section shared behind an `Arc`, without cloning it per request.
+pub struct ExtractSharedConfig<T>(pub Arc<T>);
+
+impl<S, T> FromRequestParts<S> for ExtractSharedConfig<T>
+where
+    T: ConfigItem,
+    S: Send + Sync,
+    Config: FromRef<S>,
+{
+    type Rejection = ConfigRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        crate::extract_shared(&Config::from_ref(state), parts).map(ExtractSharedConfig)
+    }
+}
+
+#[cfg(feature = "validation")]
+pub struct ExtractValidatedConfig<T>(pub T);
+
+#[cfg(feature = "validation")]
+impl<S, T> FromRequestParts<S> for ExtractValidatedConfig<T>
+where
+    T: ConfigItem + Validate,
+    S: Send + Sync,
+    Config: FromRef<S>,
+{
+    type Rejection = ConfigRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        crate::extract_validated(&Config::from_ref(state), parts).map(ExtractValidatedConfig)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+    use serde::Deserialize;
+
+    #[derive(Debug, Clone, Deserialize)]
+    struct MockConfig {
+        value: String,
+    }
+
+    impl ConfigItem for MockConfig {
+        fn key() -> &'static str {
+            "mock"
+        }
+    }
+
+    #[derive(Clone)]
+    struct AppState {
+        config: Config,
+    }
+
+    impl FromRef<AppState> for Config {
+        fn from_ref(state: &AppState) -> Self {
+            state.config.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extract_from_state() {
+        let state = AppState {
+            config: Config::builder()
+                .add_toml_str("[mock]\nvalue = \"from state\"")
+                .build()
+                .expect("failed to build config"),
+        };
+
+        let (mut parts, _) = Request::new(()).into_parts();
+
+        let ExtractConfig(mock) =
+            ExtractConfig::<MockConfig>::from_request_parts(&mut parts, &state)
+                .await
+                .unwrap_or_else(|_| panic!("failed to extract config"));
+
+        assert_eq!(mock.value, "from state");
+
+        let ExtractSharedConfig(shared) =
+            ExtractSharedConfig::<MockConfig>::from_request_parts(&mut parts, &state)
+                .await
+                .unwrap_or_else(|_| panic!("failed to extract shared config"));
+
+        assert!(Arc::ptr_eq(
+            &shared,
+            &state
+                .config
+                .get_shared::<MockConfig>()
+                .expect("cached section")
+        ));
+
+        let ExtractOptionalConfig(missing) =
+            ExtractOptionalConfig::<MockConfig>::from_request_parts(&mut parts, &Config::default())
+                .await
+                .unwrap_or_else(|_| panic!("failed to extract optional config"));
+
+        assert!(missing.is_none());
+    }
+}
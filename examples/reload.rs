@@ -0,0 +1,32 @@
+use serde::Deserialize;
+use std::time::Duration;
+use thisconfig::{Config, config};
+
+#[config(key = "app")]
+#[derive(Clone, Deserialize)]
+struct AppConfig {
+    name: String,
+    debug: bool,
+}
+
+#[tokio::main]
+async fn main() {
+    let config = Config::builder()
+        .add_dotenv()
+        .add_required_file("config.toml")
+        .build_reloadable()
+        .expect("Failed to load config file");
+
+    let _watcher = config.watch(Duration::from_secs(1));
+    let mut updates = config.subscribe();
+
+    println!("Edit config.toml to trigger a reload (Ctrl+C to exit)");
+
+    while updates.changed().await.is_ok() {
+        let app_config = updates.borrow_and_update().expect::<AppConfig>();
+        println!(
+            "Reloaded: {} (debug: {})",
+            app_config.name, app_config.debug
+        );
+    }
+}
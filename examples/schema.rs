@@ -0,0 +1,38 @@
+use serde::Deserialize;
+use thisconfig::{config, schema::ConfigSchema};
+use validator::Validate;
+
+fn default_timeout() -> u32 {
+    60
+}
+
+/// HTTP server settings.
+#[config(key = "server")]
+#[derive(Clone, Deserialize, Validate)]
+struct ServerConfig {
+    /// Interface the server binds to.
+    #[validate(length(min = 1))]
+    host: String,
+    #[validate(range(min = 1024, max = 65535))]
+    port: u16,
+    #[serde(default = "default_timeout")]
+    #[validate(range(min = 1, max = 300))]
+    timeout: u32,
+}
+
+#[config(key = "app")]
+#[derive(Clone, Deserialize, Default)]
+#[serde(default)]
+struct AppConfig {
+    name: String,
+    debug: bool,
+}
+
+fn main() {
+    let schema = ConfigSchema::new()
+        .section::<ServerConfig>()
+        .section::<AppConfig>();
+
+    println!("{}", schema.to_sample_toml());
+    println!("{}", schema.to_json_schema());
+}
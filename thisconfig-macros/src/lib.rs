@@ -1,6 +1,11 @@
+mod schema;
+
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{DeriveInput, Error, Expr, Lit, Meta, parse_macro_input};
+use syn::{
+    DeriveInput, Error, Expr, Lit, LitStr, MetaNameValue, Path, Token, parse::Parser,
+    parse_macro_input, punctuated::Punctuated,
+};
 
 #[proc_macro_attribute]
 pub fn config(args: TokenStream, input: TokenStream) -> TokenStream {
@@ -14,51 +19,77 @@ pub fn config(args: TokenStream, input: TokenStream) -> TokenStream {
 
 fn config_impl(args: TokenStream, input: &DeriveInput) -> syn::Result<TokenStream> {
     let name = &input.ident;
-    let meta = syn::parse::<Meta>(args)?;
+    let args = Punctuated::<MetaNameValue, Token![,]>::parse_terminated
+        .parse(args)
+        .map_err(|e| {
+            Error::new(
+                e.span(),
+                r#"expected format: #[config(key = "section_name")]"#,
+            )
+        })?;
 
-    let nv = meta.require_name_value().map_err(|_| {
-        Error::new_spanned(&meta, r#"expected format: #[config(key = "section_name")]"#)
-    })?;
+    let mut key = None;
+    let mut path = None;
 
-    if !nv.path.is_ident("key") {
-        return Err(Error::new_spanned(&nv.path, "expected `key` attribute"));
+    for nv in &args {
+        if nv.path.is_ident("key") {
+            key = Some(string_literal(&nv.value, "key")?);
+        } else if nv.path.is_ident("crate") {
+            path = Some(string_literal(&nv.value, "crate")?.parse::<Path>()?);
+        } else {
+            return Err(Error::new_spanned(
+                &nv.path,
+                "expected `key` or `crate` attribute",
+            ));
+        }
     }
 
-    let Expr::Lit(expr_lit) = &nv.value else {
-        return Err(Error::new_spanned(
-            &nv.value,
-            "expected string literal for key",
-        ));
-    };
-
-    let Lit::Str(lit_str) = &expr_lit.lit else {
+    let Some(lit_str) = key else {
         return Err(Error::new_spanned(
-            &expr_lit.lit,
-            "expected string literal for key",
+            &args,
+            r#"expected format: #[config(key = "section_name")]"#,
         ));
     };
 
     #[cfg(feature = "axum")]
-    let expanded = quote! {
-        #input
-
-        impl ::axum_config::ConfigItem for #name {
-            fn key() -> &'static str {
-                #lit_str
-            }
-        }
-    };
+    let default_krate = quote!(::axum_config);
 
     #[cfg(not(feature = "axum"))]
+    let default_krate = quote!(::thisconfig);
+
+    let krate = path.map_or(default_krate, |path| quote!(#path));
+
+    let schema = schema::section_schema(input, &krate);
+
     let expanded = quote! {
         #input
 
-        impl ::thisconfig::ConfigItem for #name {
+        impl #krate::ConfigItem for #name {
             fn key() -> &'static str {
                 #lit_str
             }
+
+            fn schema() -> #krate::schema::SectionSchema {
+                #schema
+            }
         }
     };
 
     Ok(expanded.into())
 }
+
+fn string_literal(value: &Expr, name: &str) -> syn::Result<LitStr> {
+    match value {
+        Expr::Lit(expr_lit) => match &expr_lit.lit {
+            Lit::Str(lit_str) => Ok(lit_str.clone()),
+            lit => Err(Error::new_spanned(
+                lit,
+                format!("expected string literal for {name}"),
+            )),
+        },
+        value => Err(Error::new_spanned(
+            value,
+            format!("expected string literal for {name}"),
+        )),
+    }
+}
@@ -0,0 +1,414 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    Attribute, Data, DeriveInput, Expr, ExprLit, ExprPath, ExprUnary, Field, Fields,
+    GenericArgument, Lit, LitStr, PathArguments, Token, Type, UnOp, meta::ParseNestedMeta,
+    token::Paren,
+};
+
+/// Default declared through `#[serde(default)]` or `#[serde(default = "path")]`.
+enum DefaultAttr {
+    Trait,
+    Path(ExprPath),
+}
+
+#[derive(Default)]
+struct ContainerAttrs {
+    rename_all: Option<String>,
+    default: Option<DefaultAttr>,
+    deny_unknown_fields: bool,
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+    rename: Option<String>,
+    default: Option<DefaultAttr>,
+    skip: bool,
+    flatten: bool,
+}
+
+/// Builds the expression returning the `SectionSchema` of `input`.
+///
+/// Serde and validator attributes are read leniently: anything that cannot be
+/// understood is ignored here and reported by the respective derive instead.
+pub fn section_schema(input: &DeriveInput, krate: &TokenStream) -> TokenStream {
+    let container = container_attrs(&input.attrs);
+    let doc = doc_expr(&input.attrs);
+    let deny_unknown_fields = container.deny_unknown_fields;
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(named) => named
+                .named
+                .iter()
+                .filter_map(|field| field_schema(field, &container, krate))
+                .collect(),
+            _ => Vec::new(),
+        },
+        _ => Vec::new(),
+    };
+
+    quote! {
+        #krate::schema::SectionSchema {
+            key: <Self as #krate::ConfigItem>::key(),
+            doc: #doc,
+            fields: {
+                let mut fields = ::std::vec::Vec::new();
+                #(#fields)*
+                fields
+            },
+            deny_unknown_fields: #deny_unknown_fields,
+        }
+    }
+}
+
+/// Builds the statement pushing the schema of `field` onto `fields`.
+///
+/// A flattened `#[config]` type contributes its fields, optional when the flattened
+/// field has a default; any other flattened type, such as a catch-all map, adds none.
+fn field_schema(
+    field: &Field,
+    container: &ContainerAttrs,
+    krate: &TokenStream,
+) -> Option<TokenStream> {
+    let attrs = field_attrs(&field.attrs);
+
+    if attrs.skip {
+        return None;
+    }
+
+    let ident = field.ident.as_ref()?;
+    let ty = &field.ty;
+
+    if attrs.flatten {
+        let optional = option_inner(ty);
+        let inner = optional.unwrap_or(ty);
+        let flattened = quote! {{
+            #[allow(unused_imports)]
+            use #krate::schema::{NestedSection as _, NotNested as _};
+            (&#krate::schema::TypeProbe::<#inner>::new()).nested_fields()
+        }};
+
+        if attrs.default.is_none() && container.default.is_none() && optional.is_none() {
+            return Some(quote!(fields.extend(#flattened);));
+        }
+
+        return Some(quote! {
+            fields.extend(#flattened.into_iter().map(|mut field| {
+                field.required = false;
+                field
+            }));
+        });
+    }
+
+    let name = attrs.rename.unwrap_or_else(|| {
+        let name = ident.to_string();
+        let name = name.strip_prefix("r#").unwrap_or(&name);
+
+        rename(name, container.rename_all.as_deref())
+    });
+
+    let default = match (&attrs.default, &container.default) {
+        (Some(DefaultAttr::Trait), _) => Some(quote!(<#ty as ::core::default::Default>::default())),
+        (Some(DefaultAttr::Path(path)), _) => Some(quote!(#path())),
+        (None, Some(DefaultAttr::Trait)) => {
+            Some(quote!(<Self as ::core::default::Default>::default().#ident))
+        }
+        (None, Some(DefaultAttr::Path(path))) => Some(quote!(#path().#ident)),
+        (None, None) => None,
+    };
+
+    let required = default.is_none() && !is_option(ty);
+
+    let default = match default {
+        Some(expr) => quote! {{
+            #[allow(unused_imports)]
+            use #krate::schema::{NoDefault as _, SerializeDefault as _};
+            (&#krate::schema::DefaultProbe(#expr)).default_value()
+        }},
+        None => quote!(::core::option::Option::None),
+    };
+
+    let ty_name = quote!(#ty).to_string().replace(' ', "");
+    let doc = doc_expr(&field.attrs);
+    let constraints = constraints(&field.attrs, krate);
+
+    // Resolved on the field type itself, so aliases and qualified paths are covered.
+    let secret = quote! {{
+        #[allow(unused_imports)]
+        use #krate::schema::{NotSecret as _, SecretField as _};
+        (&#krate::schema::TypeProbe::<#ty>::new()).is_secret()
+    }};
+
+    let nested = quote! {{
+        #[allow(unused_imports)]
+        use #krate::schema::{NestedSection as _, NotNested as _};
+        (&#krate::schema::TypeProbe::<#ty>::new()).nested_fields()
+    }};
+
+    Some(quote! {
+        fields.push(#krate::schema::FieldSchema {
+            name: ::std::string::String::from(#name),
+            ty: #ty_name,
+            doc: #doc,
+            required: #required,
+            default: #default,
+            constraints: ::std::vec![#(#constraints),*],
+            secret: #secret,
+            fields: #nested,
+        });
+    })
+}
+
+fn container_attrs(attrs: &[Attribute]) -> ContainerAttrs {
+    let mut container = ContainerAttrs::default();
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename_all") {
+                container.rename_all = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("default") {
+                container.default = Some(parse_default(&meta)?);
+            } else if meta.path.is_ident("deny_unknown_fields") {
+                container.deny_unknown_fields = true;
+            } else {
+                skip(&meta)?;
+            }
+
+            Ok(())
+        });
+    }
+
+    container
+}
+
+fn field_attrs(attrs: &[Attribute]) -> FieldAttrs {
+    let mut field = FieldAttrs::default();
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") && meta.input.peek(Token![=]) {
+                field.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("default") {
+                field.default = Some(parse_default(&meta)?);
+            } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_deserializing") {
+                field.skip = true;
+            } else if meta.path.is_ident("flatten") {
+                field.flatten = true;
+            } else {
+                skip(&meta)?;
+            }
+
+            Ok(())
+        });
+    }
+
+    field
+}
+
+fn parse_default(meta: &ParseNestedMeta) -> syn::Result<DefaultAttr> {
+    if meta.input.peek(Token![=]) {
+        let path = meta.value()?.parse::<LitStr>()?.parse::<ExprPath>()?;
+        Ok(DefaultAttr::Path(path))
+    } else {
+        Ok(DefaultAttr::Trait)
+    }
+}
+
+/// Consumes the arguments of an attribute we do not interpret.
+fn skip(meta: &ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(Token![=]) {
+        meta.value()?.parse::<Expr>()?;
+    } else if meta.input.peek(Paren) {
+        let content;
+        syn::parenthesized!(content in meta.input);
+        content.parse::<TokenStream>()?;
+    }
+
+    Ok(())
+}
+
+fn constraints(attrs: &[Attribute], krate: &TokenStream) -> Vec<TokenStream> {
+    let mut constraints = Vec::new();
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("validate")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            let Some(ident) = meta.path.get_ident().map(ToString::to_string) else {
+                return skip(&meta);
+            };
+
+            match ident.as_str() {
+                "range" => {
+                    let (mut min, mut max) = (None, None);
+
+                    meta.parse_nested_meta(|inner| {
+                        if inner.path.is_ident("min") {
+                            min = number(&inner.value()?.parse()?);
+                        } else if inner.path.is_ident("max") {
+                            max = number(&inner.value()?.parse()?);
+                        } else {
+                            skip(&inner)?;
+                        }
+
+                        Ok(())
+                    })?;
+
+                    let (min, max) = (option(min), option(max));
+
+                    constraints.push(quote! {
+                        #krate::schema::Constraint::Range { min: #min, max: #max }
+                    });
+                }
+                "length" => {
+                    let (mut min, mut max) = (None, None);
+
+                    meta.parse_nested_meta(|inner| {
+                        let value = if inner.path.is_ident("min")
+                            || inner.path.is_ident("max")
+                            || inner.path.is_ident("equal")
+                        {
+                            number(&inner.value()?.parse()?).map(|n| n as u64)
+                        } else {
+                            return skip(&inner);
+                        };
+
+                        if !inner.path.is_ident("max") {
+                            min = value;
+                        }
+
+                        if !inner.path.is_ident("min") {
+                            max = value;
+                        }
+
+                        Ok(())
+                    })?;
+
+                    let (min, max) = (option(min), option(max));
+
+                    constraints.push(quote! {
+                        #krate::schema::Constraint::Length { min: #min, max: #max }
+                    });
+                }
+                "email" => {
+                    constraints.push(quote!(#krate::schema::Constraint::Email));
+                    skip(&meta)?;
+                }
+                "url" => {
+                    constraints.push(quote!(#krate::schema::Constraint::Url));
+                    skip(&meta)?;
+                }
+                _ => {
+                    constraints.push(quote!(#krate::schema::Constraint::Other(#ident)));
+                    skip(&meta)?;
+                }
+            }
+
+            Ok(())
+        });
+    }
+
+    constraints
+}
+
+/// Evaluates a numeric literal, optionally negated.
+fn number(expr: &Expr) -> Option<f64> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(int), ..
+        }) => int.base10_parse::<f64>().ok(),
+        Expr::Lit(ExprLit {
+            lit: Lit::Float(float),
+            ..
+        }) => float.base10_parse::<f64>().ok(),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => number(expr).map(|n| -n),
+        _ => None,
+    }
+}
+
+fn option<T: quote::ToTokens>(value: Option<T>) -> TokenStream {
+    match value {
+        Some(value) => quote!(::core::option::Option::Some(#value)),
+        None => quote!(::core::option::Option::None),
+    }
+}
+
+fn doc_expr(attrs: &[Attribute]) -> TokenStream {
+    let lines = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta.require_name_value().ok()?.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(doc), ..
+            }) => Some(doc.value()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    option((!lines.is_empty()).then(|| lines.join("\n")))
+}
+
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
+
+/// The `T` of an `Option<T>` field type.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+
+    let segment = path.path.segments.last()?;
+
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first()? {
+            GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Applies a serde `rename_all` rule to a snake_case field name.
+fn rename(name: &str, rule: Option<&str>) -> String {
+    let capitalize = |word: &str| {
+        let mut chars = word.chars();
+
+        chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+            .unwrap_or_default()
+    };
+
+    match rule {
+        Some("lowercase") => name.to_lowercase(),
+        Some("UPPERCASE" | "SCREAMING_SNAKE_CASE") => name.to_uppercase(),
+        Some("PascalCase") => name.split('_').map(capitalize).collect(),
+        Some("camelCase") => {
+            let pascal = name.split('_').map(capitalize).collect::<String>();
+            let mut chars = pascal.chars();
+
+            chars
+                .next()
+                .map(|first| first.to_lowercase().chain(chars).collect())
+                .unwrap_or_default()
+        }
+        Some("kebab-case") => name.replace('_', "-"),
+        Some("SCREAMING-KEBAB-CASE") => name.replace('_', "-").to_uppercase(),
+        _ => name.to_string(),
+    }
+}
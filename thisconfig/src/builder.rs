@@ -1,17 +1,72 @@
-use crate::{interpolation::Interpolator, Config, ConfigError, ConfigSourceInfo};
-use std::{fs, path::PathBuf, sync::Arc};
-use toml::Table;
+#[cfg(feature = "reload")]
+use crate::ReloadableConfig;
+use crate::{
+    Config, ConfigError, ConfigItem, ConfigSourceInfo, FileFormat, glob,
+    interpolation::{self, InterpolationMode, Interpolator, Resolver},
+    merge::{ArrayMerge, MergeOptions},
+    provenance::{self, Locations, Provenance},
+};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+use toml::{Table, Value};
 use tracing::{error, warn};
 
-#[derive(Debug)]
+#[cfg(feature = "validation")]
+use validator::Validate;
+
+#[derive(Debug, Clone)]
 enum Source {
-    File { path: PathBuf, required: bool },
-    TomlString { content: String },
+    File {
+        path: PathBuf,
+        required: bool,
+    },
+    Directory {
+        path: PathBuf,
+        required: bool,
+    },
+    ExeRelative {
+        path: PathBuf,
+        required: bool,
+    },
+    Search {
+        app: String,
+        file: PathBuf,
+        required: bool,
+    },
+    TomlString {
+        content: String,
+    },
+    Environment {
+        prefix: String,
+        separator: String,
+    },
+    Profiled {
+        path: PathBuf,
+    },
 }
 
-#[derive(Debug, Default)]
+/// Environment variable used to select the profile when none is set on the builder.
+const PROFILE_ENV: &str = "THISCONFIG_PROFILE";
+
+/// Check run against the built configuration for a section registered with `require`.
+type Requirement = fn(&Config) -> Result<(), ConfigError>;
+
+#[derive(Debug, Clone, Default)]
 pub struct ConfigBuilder {
     sources: Vec<Source>,
+    profile: Option<String>,
+    /// `key.path=value` assignments applied after every other source.
+    overrides: Vec<String>,
+    interpolation: InterpolationMode,
+    interpolator: Interpolator,
+    merge: MergeOptions,
+    /// Name of the top-level directive including other files, disabled when `None`.
+    include_key: Option<String>,
+    requirements: Vec<Requirement>,
 }
 
 impl ConfigBuilder {
@@ -33,6 +88,82 @@ impl ConfigBuilder {
         self
     }
 
+    /// Adds a file resolved relative to the directory of the running executable,
+    /// independently of the working directory.
+    pub fn add_file_relative_to_exe<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.sources.push(Source::ExeRelative {
+            path: path.into(),
+            required: false,
+        });
+
+        self
+    }
+
+    /// Like `add_file_relative_to_exe`, but fails if the file does not exist.
+    pub fn add_required_file_relative_to_exe<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.sources.push(Source::ExeRelative {
+            path: path.into(),
+            required: true,
+        });
+
+        self
+    }
+
+    /// Adds the first existing `file` among the usual locations for `app`:
+    ///
+    /// 1. the working directory: `./{file}`
+    /// 2. `$XDG_CONFIG_HOME/{app}/{file}`, or `$HOME/.config/{app}/{file}`
+    /// 3. `/etc/{app}/{file}`
+    /// 4. the directory of the executable
+    ///
+    /// The resolved path is recorded in `Config::sources`. Nothing is loaded if no
+    /// candidate exists.
+    pub fn add_searched_file<P: Into<PathBuf>>(mut self, app: &str, file: P) -> Self {
+        self.sources.push(Source::Search {
+            app: app.to_string(),
+            file: file.into(),
+            required: false,
+        });
+
+        self
+    }
+
+    /// Like `add_searched_file`, but fails if the file exists in none of the locations.
+    pub fn add_required_searched_file<P: Into<PathBuf>>(mut self, app: &str, file: P) -> Self {
+        self.sources.push(Source::Search {
+            app: app.to_string(),
+            file: file.into(),
+            required: true,
+        });
+
+        self
+    }
+
+    /// Adds every configuration file of a directory, like a `conf.d` folder.
+    ///
+    /// Files with a `.toml` extension, plus `.yaml`/`.yml` and `.json` when the `yaml`
+    /// and `json` features are enabled, are loaded in lexical order of their names;
+    /// hidden entries, subdirectories and other files are skipped. A missing or empty
+    /// directory is ignored with a warning.
+    pub fn add_dir<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.sources.push(Source::Directory {
+            path: path.into(),
+            required: false,
+        });
+
+        self
+    }
+
+    /// Like `add_dir`, but fails if the directory is missing or has no configuration files.
+    pub fn add_required_dir<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.sources.push(Source::Directory {
+            path: path.into(),
+            required: true,
+        });
+
+        self
+    }
+
     pub fn add_toml_str(mut self, toml: &str) -> Self {
         self.sources.push(Source::TomlString {
             content: toml.to_string(),
@@ -41,6 +172,170 @@ impl ConfigBuilder {
         self
     }
 
+    /// Adds environment variables starting with `{prefix}{separator}` as a source.
+    ///
+    /// The remaining name is split on `separator` and lowercased to build the key path,
+    /// so `APP__DATABASE__PORT=5432` with `add_env_prefix("APP", "__")` becomes
+    /// `[database] port = 5432`. Numbers, booleans and datetimes are typed when they
+    /// read back unchanged, arrays, inline tables and quoted strings are parsed as TOML
+    /// literals, and anything else (`1.10`, `s3cr3t`) stays a string.
+    pub fn add_env_prefix(mut self, prefix: &str, separator: &str) -> Self {
+        self.sources.push(Source::Environment {
+            prefix: prefix.to_string(),
+            separator: separator.to_string(),
+        });
+
+        self
+    }
+
+    /// Adds command-line style overrides such as `database.port=5433`.
+    ///
+    /// Values are parsed as TOML literals, falling back to plain strings. Overrides are
+    /// always merged last, whatever the order of the builder calls, and appear as
+    /// `ConfigSourceInfo::CommandLine` in the provenance.
+    pub fn add_overrides<I, K, V>(mut self, overrides: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        self.overrides.extend(
+            overrides
+                .into_iter()
+                .map(|(key, value)| format!("{}={}", key.as_ref(), value.as_ref())),
+        );
+
+        self
+    }
+
+    /// Adds the `--set key.path=value` (or `--set=key.path=value`) pairs found in `args`,
+    /// e.g. `std::env::args()`. Other arguments are ignored; see `add_overrides`.
+    ///
+    /// A `--set` without a `key=value` assignment makes `build` fail with
+    /// `ConfigError::InvalidOverride`.
+    pub fn add_args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let arg = arg.as_ref();
+
+            if let Some(assignment) = arg.strip_prefix("--set=") {
+                self.overrides.push(assignment.to_string());
+            } else if arg == "--set" {
+                let assignment = args.next();
+                self.overrides
+                    .push(assignment.as_ref().map_or("", AsRef::as_ref).to_string());
+            }
+        }
+
+        self
+    }
+
+    /// Sets the profile used by `add_profiled_file`, overriding `THISCONFIG_PROFILE`.
+    pub fn with_profile(mut self, profile: &str) -> Self {
+        self.profile = Some(profile.to_string());
+        self
+    }
+
+    /// Sets when `${...}` and `file:` placeholders are expanded for file and string sources.
+    ///
+    /// Defaults to `InterpolationMode::Raw`. Prefer `InterpolationMode::Values` when
+    /// placeholders take values from the environment, so they cannot alter the document.
+    pub fn with_interpolation_mode(mut self, mode: InterpolationMode) -> Self {
+        self.interpolation = mode;
+        self
+    }
+
+    /// Registers a resolver for `${scheme:argument}` placeholders.
+    ///
+    /// The built-in `env` and `file` schemes can be replaced the same way, which also
+    /// changes how `${VAR}` and `file:path` placeholders are resolved.
+    ///
+    /// ```ignore
+    /// let config = Config::builder()
+    ///     .add_resolver("vault", |path: &str| vault.read(path).map_err(|e| e.to_string()))
+    ///     .add_required_file("config.toml")
+    ///     .build()?;
+    /// ```
+    pub fn add_resolver<R: Resolver>(mut self, scheme: &str, resolver: R) -> Self {
+        self.interpolator.register(scheme, resolver);
+        self
+    }
+
+    /// Sets how arrays found in several sources are merged. Defaults to `ArrayMerge::Replace`.
+    pub fn with_array_merge(mut self, strategy: ArrayMerge) -> Self {
+        self.merge.set_arrays(strategy);
+        self
+    }
+
+    /// Sets the array merge strategy for a dotted key path such as `proxy.upstreams`.
+    ///
+    /// The path may be a glob pattern (`**.upstreams`). Later rules take precedence
+    /// over earlier ones and over `with_array_merge`.
+    pub fn with_array_merge_at(mut self, path: &str, strategy: ArrayMerge) -> Self {
+        self.merge.set_arrays_at(path, strategy);
+        self
+    }
+
+    /// Enables the top-level directive listing keys to remove, under the name `key`
+    /// (usually `"unset"`). Disabled by default, so such a key is plain configuration.
+    ///
+    /// With `with_unset_key("unset")`, a source containing `unset = ["cache", "db.replica"]`
+    /// removes those dotted keys, including whole tables, from the configuration merged
+    /// so far before its own keys are merged. Only an array of strings is treated as the
+    /// directive.
+    pub fn with_unset_key(mut self, key: &str) -> Self {
+        self.merge.set_unset_key(key);
+        self
+    }
+
+    /// Enables the top-level directive listing files to merge before the including
+    /// file, under the name `key` (usually `"include"`). Disabled by default, so such
+    /// a key is plain configuration.
+    ///
+    /// Entries are paths relative to the including file, or glob patterns expanded in
+    /// sorted order. Included files may include others; a cycle fails the build.
+    pub fn with_include_key(mut self, key: &str) -> Self {
+        self.include_key = Some(key.to_string());
+        self
+    }
+
+    /// Adds a base file followed by its profile and local layers.
+    ///
+    /// For `config.toml` and the profile `production` this loads, in order:
+    /// `config.toml` (required), `config.production.toml` (optional) and
+    /// `config.local.toml` (optional). The profile comes from `with_profile` or the
+    /// `THISCONFIG_PROFILE` environment variable; without one, the profile layer is skipped.
+    pub fn add_profiled_file<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.sources.push(Source::Profiled { path: path.into() });
+        self
+    }
+
+    /// Registers a section that must be present and deserializable when the
+    /// configuration is built.
+    ///
+    /// `build` checks every registered section up front and fails with
+    /// `ConfigError::InvalidSections` listing all of the bad ones.
+    pub fn require<T: ConfigItem>(mut self) -> Self {
+        self.requirements
+            .push(|config| config.try_get::<T>().map(|_| ()));
+
+        self
+    }
+
+    #[cfg(feature = "validation")]
+    /// Like `require`, but also runs `validate()` on the section at build time.
+    pub fn require_validated<T: ConfigItem + Validate>(mut self) -> Self {
+        self.requirements
+            .push(|config| config.get_validated::<T>().map(|_| ()));
+
+        self
+    }
+
     #[cfg(feature = "dotenv")]
     /// Loads environment variables from a specified `.env` file following the
     /// [dotenv](https://crates.io/crates/dotenv) convention.
@@ -57,108 +352,664 @@ impl ConfigBuilder {
         self
     }
 
-    fn load(sources: Vec<Source>) -> Result<Config, ConfigError> {
-        let mut merged = Table::new();
-        let mut source_infos = Vec::new();
+    fn load(self) -> Result<Config, ConfigError> {
+        let profile = self
+            .profile
+            .or_else(|| env::var(PROFILE_ENV).ok())
+            .filter(|profile| !profile.is_empty());
+
+        let sources = Self::expand_profiles(self.sources, profile.as_deref());
+
+        let mut loader = Loader {
+            interpolator: self.interpolator,
+            mode: self.interpolation,
+            merge: self.merge,
+            include_key: self.include_key,
+            merged: Table::new(),
+            provenance: Provenance::default(),
+            sources: Vec::new(),
+            #[cfg(feature = "reload")]
+            watched: Vec::new(),
+        };
 
         for source in sources {
             match source {
-                Source::File { path, required } => {
-                    if path.exists() {
-                        source_infos.push(ConfigSourceInfo::File {
-                            path: path.clone(),
-                            required,
-                            found: true,
-                        });
-
-                        let content = fs::read_to_string(&path)?;
-                        let interpolated = Interpolator::interpolate(&content)
-                            .inspect_err(|e| {
-                                error!("Interpolation error in file {}: {e}", path.display());
-                            })
-                            .map_err(ConfigError::interpolation_error)?;
-
-                        let table = toml::from_str::<Table>(&interpolated).inspect_err(|e| {
-                            error!("Failed to parse TOML from {}: {}", path.display(), e);
-                        })?;
-
-                        Self::merge_tables(&mut merged, table);
-                    } else if required {
-                        source_infos.push(ConfigSourceInfo::File {
-                            path: path.clone(),
-                            required,
-                            found: false,
-                        });
-
-                        error!("Config file not found (required): {}", path.display());
-
-                        return Err(ConfigError::FileNotFound(
-                            path.to_str().unwrap_or_default().to_string(),
-                        ));
-                    } else {
-                        source_infos.push(ConfigSourceInfo::File {
-                            path: path.clone(),
-                            required,
-                            found: false,
-                        });
-
-                        warn!("Config file not found (optional): {}", path.display());
-                    }
-                }
-                Source::TomlString { content } => {
-                    source_infos.push(ConfigSourceInfo::TomlString);
+                Source::File { path, required } => loader.load_file(&path, required, &[])?,
+                Source::Directory { path, required } => loader.load_dir(&path, required)?,
+                Source::ExeRelative { path, required } => match Self::exe_dir() {
+                    Ok(dir) => loader.load_file(&dir.join(path), required, &[])?,
+                    Err(e) if required => return Err(e),
+                    Err(_) => warn!(
+                        "Skipping {} (optional): executable directory not found",
+                        path.display()
+                    ),
+                },
+                Source::Search {
+                    app,
+                    file,
+                    required,
+                } => {
+                    let candidates =
+                        Self::search_candidates(&app, &file, Self::config_home().as_deref());
+
+                    let found = candidates.iter().position(|candidate| candidate.is_file());
 
-                    let expanded = Interpolator::interpolate(&content)
-                        .inspect_err(|e| {
-                            error!("Interpolation error in TOML string: {e}");
-                        })
-                        .map_err(ConfigError::interpolation_error)?;
+                    // A missing candidate ahead of the one found would take precedence once created.
+                    #[cfg(feature = "reload")]
+                    loader
+                        .watched
+                        .extend_from_slice(&candidates[..found.unwrap_or(candidates.len())]);
 
-                    let table: Table = toml::from_str::<Table>(&expanded).inspect_err(|e| {
-                        error!("Failed to parse TOML string: {}", e);
-                    })?;
+                    match found.map(|index| &candidates[index]) {
+                        Some(path) => loader.load_file(path, required, &[])?,
+                        None if required => {
+                            let searched = candidates
+                                .iter()
+                                .map(|candidate| candidate.display().to_string())
+                                .collect::<Vec<_>>()
+                                .join(", ");
 
-                    Self::merge_tables(&mut merged, table);
+                            error!("Config file not found (required), searched: {searched}");
+                            return Err(ConfigError::FileNotFound(searched));
+                        }
+                        None => warn!(
+                            "Config file {} not found for '{app}' (optional)",
+                            file.display()
+                        ),
+                    }
+                }
+                Source::TomlString { content } => loader.load_toml_str(&content)?,
+                Source::Profiled { .. } => unreachable!("profiled sources are expanded"),
+                Source::Environment { prefix, separator } => {
+                    let table = Self::env_table(&prefix, &separator);
+                    let info = ConfigSourceInfo::Environment { prefix, separator };
+
+                    loader.apply(table, info, &Locations::new());
                 }
             }
         }
 
+        if !self.overrides.is_empty() {
+            let table = Self::overrides_table(&self.overrides)?;
+            loader.apply(table, ConfigSourceInfo::CommandLine, &Locations::new());
+        }
+
+        let Loader {
+            mut merged,
+            provenance,
+            sources,
+            #[cfg(feature = "reload")]
+            watched,
+            ..
+        } = loader;
+
+        // Environment and command-line values are taken verbatim, `$${` included.
+        let literal = |path: &str| {
+            provenance.origin(path).is_some_and(|origin| {
+                matches!(
+                    origin.source,
+                    ConfigSourceInfo::Environment { .. } | ConfigSourceInfo::CommandLine
+                )
+            })
+        };
+
+        interpolation::resolve_references(&mut merged, &literal)
+            .inspect_err(|e| error!("Failed to resolve config references: {e}"))
+            .map_err(ConfigError::interpolation_error)?;
+
         Ok(Config {
             inner: Arc::new(merged),
-            sources: Arc::from(source_infos),
+            sources: Arc::from(sources),
+            #[cfg(feature = "reload")]
+            watched: Arc::from(watched),
+            provenance: Arc::new(provenance),
+            profile: profile.map(Arc::from),
+            loaded_at: Some(SystemTime::now()),
+            cache: Arc::default(),
         })
     }
 
-    fn merge_tables(base: &mut Table, other: Table) {
-        for (key, value) in other {
-            match base.get_mut(&key) {
-                Some(existing)
-                    if matches!(existing, toml::Value::Table(_))
-                        && matches!(value, toml::Value::Table(_)) =>
-                {
-                    if let (toml::Value::Table(base_table), toml::Value::Table(other_table)) =
-                        (existing, value)
-                    {
-                        Self::merge_tables(base_table, other_table);
+    fn exe_dir() -> Result<PathBuf, ConfigError> {
+        env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(Path::to_path_buf))
+            .ok_or(ConfigError::ExeDirNotFound)
+    }
+
+    /// `$XDG_CONFIG_HOME`, or `$HOME/.config` when it is unset or empty.
+    fn config_home() -> Option<PathBuf> {
+        env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    }
+
+    /// Candidate locations of `file` for `app`, in lookup order.
+    pub(crate) fn search_candidates(
+        app: &str,
+        file: &Path,
+        config_home: Option<&Path>,
+    ) -> Vec<PathBuf> {
+        let mut candidates = vec![file.to_path_buf()];
+
+        candidates.extend(config_home.map(|dir| dir.join(app).join(file)));
+        candidates.push(Path::new("/etc").join(app).join(file));
+        candidates.extend(Self::exe_dir().ok().map(|dir| dir.join(file)));
+
+        candidates
+    }
+
+    fn expand_profiles(sources: Vec<Source>, profile: Option<&str>) -> Vec<Source> {
+        let mut expanded = Vec::with_capacity(sources.len());
+
+        for source in sources {
+            let Source::Profiled { path } = source else {
+                expanded.push(source);
+                continue;
+            };
+
+            expanded.push(Source::File {
+                path: path.clone(),
+                required: true,
+            });
+
+            if let Some(profile) = profile {
+                expanded.push(Source::File {
+                    path: Self::layer_path(&path, profile),
+                    required: false,
+                });
+            }
+
+            expanded.push(Source::File {
+                path: Self::layer_path(&path, "local"),
+                required: false,
+            });
+        }
+
+        expanded
+    }
+
+    /// Inserts `layer` before the extension: `config.toml` becomes `config.{layer}.toml`.
+    fn layer_path(path: &Path, layer: &str) -> PathBuf {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+
+        let file_name = match path.extension() {
+            Some(ext) => format!("{stem}.{layer}.{}", ext.to_string_lossy()),
+            None => format!("{stem}.{layer}"),
+        };
+
+        path.with_file_name(file_name)
+    }
+
+    fn env_table(prefix: &str, separator: &str) -> Table {
+        let mut table = Table::new();
+
+        if separator.is_empty() {
+            warn!("Ignoring environment source '{prefix}' with an empty separator");
+            return table;
+        }
+
+        let prefix = format!("{prefix}{separator}");
+
+        // Unrelated variables may hold anything, so only matching names must be UTF-8.
+        let mut vars = env::vars_os()
+            .filter(|(name, _)| name.as_encoded_bytes().starts_with(prefix.as_bytes()))
+            .filter_map(
+                |(name, value)| match (name.into_string(), value.into_string()) {
+                    (Ok(name), Ok(value)) => Some((name, value)),
+                    (Ok(name), Err(_)) => {
+                        warn!("Ignoring environment variable with a non UTF-8 value: {name}");
+                        None
                     }
-                }
-                _ => {
-                    base.insert(key, value);
-                }
+                    (Err(name), _) => {
+                        warn!("Ignoring environment variable with a non UTF-8 name: {name:?}");
+                        None
+                    }
+                },
+            )
+            .collect::<Vec<_>>();
+
+        vars.sort();
+
+        for (name, raw) in vars {
+            let keys = name[prefix.len()..]
+                .split(separator)
+                .map(str::to_lowercase)
+                .collect::<Vec<_>>();
+
+            if keys.iter().any(String::is_empty) {
+                warn!("Ignoring environment variable with malformed key: {name}");
+                continue;
             }
+
+            MergeOptions::default().merge(&mut table, Self::nested(&keys, Self::env_value(raw)));
         }
+
+        table
+    }
+
+    /// Builds the table of `--set key.path=value` overrides, in order.
+    fn overrides_table(overrides: &[String]) -> Result<Table, ConfigError> {
+        let mut table = Table::new();
+
+        for assignment in overrides {
+            let keys = assignment
+                .split_once('=')
+                .map(|(key, raw)| (key.trim().split('.').collect::<Vec<_>>(), raw))
+                .filter(|(keys, _)| keys.iter().all(|key| !key.is_empty()));
+
+            let Some((keys, raw)) = keys else {
+                error!("Invalid configuration override: {assignment}");
+                return Err(ConfigError::InvalidOverride {
+                    assignment: assignment.clone(),
+                });
+            };
+
+            MergeOptions::default()
+                .merge(&mut table, Self::nested(&keys, Self::parse_literal(raw)));
+        }
+
+        Ok(table)
+    }
+
+    /// Wraps `value` in one table per key, so `["a", "b"]` becomes `[a] b = value`.
+    fn nested<K: AsRef<str>>(keys: &[K], value: Value) -> Table {
+        let mut nested = Table::new();
+        nested.insert(keys[keys.len() - 1].as_ref().to_string(), value);
+
+        for key in keys[..keys.len() - 1].iter().rev() {
+            let mut parent = Table::new();
+            parent.insert(key.as_ref().to_string(), Value::Table(nested));
+            nested = parent;
+        }
+
+        nested
+    }
+
+    /// Types an environment value only when that loses nothing: `5432` becomes an
+    /// integer but `1.10` stays a string. Arrays and inline tables are parsed as TOML
+    /// literals, and a quoted `"123456"` is unquoted into a string. Numbers still fill
+    /// `String` fields, e.g. a `123456` password.
+    fn env_value(raw: String) -> Value {
+        if raw.trim_start().starts_with(['[', '{']) {
+            return Self::parse_literal(&raw);
+        }
+
+        match raw.parse::<Value>() {
+            Ok(value @ (Value::Integer(_) | Value::Float(_) | Value::Boolean(_)))
+                if value.to_string() == raw =>
+            {
+                value
+            }
+            Ok(value @ Value::Datetime(_)) if value.to_string() == raw => value,
+            Ok(value @ Value::String(_)) if raw.starts_with('"') => value,
+            _ => Value::String(raw),
+        }
+    }
+
+    /// Parses a raw value with TOML literal semantics, falling back to a plain string.
+    fn parse_literal(raw: &str) -> Value {
+        raw.parse::<Value>()
+            .unwrap_or_else(|_| Value::String(raw.to_string()))
     }
 
     /// Builds the configuration from added sources.
     ///
     /// # Errors
     ///
-    /// Returns `ConfigError` if no sources, files missing, or parsing fails.
+    /// Returns `ConfigError` if no sources, files missing, parsing fails, or any
+    /// section registered with `require` is invalid.
     pub fn build(self) -> Result<Config, ConfigError> {
-        if self.sources.is_empty() {
+        if self.sources.is_empty() && self.overrides.is_empty() {
             return Err(ConfigError::NoSourcesConfigured);
         }
 
-        Self::load(self.sources)
+        let requirements = self.requirements.clone();
+        let config = self.load()?;
+
+        let errors = requirements
+            .iter()
+            .filter_map(|requirement| requirement(&config).err())
+            .collect::<Vec<_>>();
+
+        if !errors.is_empty() {
+            error!(
+                "{} required configuration section(s) failed to load",
+                errors.len()
+            );
+            return Err(ConfigError::InvalidSections { errors });
+        }
+
+        Ok(config)
+    }
+
+    #[cfg(feature = "reload")]
+    /// Builds a configuration handle that can be reloaded from the same sources.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError` if the initial build fails.
+    pub fn build_reloadable(self) -> Result<ReloadableConfig, ConfigError> {
+        if self.sources.is_empty() && self.overrides.is_empty() {
+            return Err(ConfigError::NoSourcesConfigured);
+        }
+
+        ReloadableConfig::new(self)
+    }
+}
+
+/// State accumulated while merging sources in order.
+struct Loader {
+    interpolator: Interpolator,
+    mode: InterpolationMode,
+    merge: MergeOptions,
+    include_key: Option<String>,
+    merged: Table,
+    provenance: Provenance,
+    sources: Vec<ConfigSourceInfo>,
+    /// Files and directories read or probed so far, found or not.
+    #[cfg(feature = "reload")]
+    watched: Vec<PathBuf>,
+}
+
+impl Loader {
+    /// Loads a file and, first, the files it includes.
+    ///
+    /// `chain` holds the canonical paths of the files currently being included,
+    /// used to detect include cycles.
+    fn load_file(
+        &mut self,
+        path: &Path,
+        required: bool,
+        chain: &[PathBuf],
+    ) -> Result<(), ConfigError> {
+        #[cfg(feature = "reload")]
+        self.watched.push(path.to_path_buf());
+
+        if !path.exists() {
+            self.sources.push(ConfigSourceInfo::File {
+                path: path.to_path_buf(),
+                required,
+                found: false,
+            });
+
+            if required {
+                error!("Config file not found (required): {}", path.display());
+
+                return Err(ConfigError::FileNotFound(
+                    path.to_str().unwrap_or_default().to_string(),
+                ));
+            }
+
+            warn!("Config file not found (optional): {}", path.display());
+            return Ok(());
+        }
+
+        let canonical = fs::canonicalize(path)?;
+
+        if chain.contains(&canonical) {
+            let mut cycle = chain.to_vec();
+            cycle.push(canonical);
+
+            error!("Include cycle detected at {}", path.display());
+            return Err(ConfigError::IncludeCycle { paths: cycle });
+        }
+
+        let content = fs::read_to_string(path)?;
+        let format = FileFormat::from_path(path);
+
+        let (mut table, locations) = self
+            .parse(&content, format)
+            .inspect_err(|e| error!("Failed to load {}: {e}", path.display()))?;
+
+        let includes = match &self.include_key {
+            Some(key) => Self::take_includes(&mut table, key, path, &canonical)?,
+            None => Vec::new(),
+        };
+
+        if !includes.is_empty() {
+            let chain = [chain, &[canonical]].concat();
+
+            for include in includes {
+                self.load_file(&include, true, &chain)?;
+            }
+        }
+
+        let info = ConfigSourceInfo::File {
+            path: path.to_path_buf(),
+            required,
+            found: true,
+        };
+
+        self.apply(table, info, &locations);
+
+        Ok(())
+    }
+
+    fn load_dir(&mut self, dir: &Path, required: bool) -> Result<(), ConfigError> {
+        #[cfg(feature = "reload")]
+        self.watched.push(dir.to_path_buf());
+
+        if !dir.is_dir() {
+            if required {
+                error!("Config directory not found (required): {}", dir.display());
+
+                return Err(ConfigError::FileNotFound(
+                    dir.to_str().unwrap_or_default().to_string(),
+                ));
+            }
+
+            warn!("Config directory not found (optional): {}", dir.display());
+            return Ok(());
+        }
+
+        let mut files = fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        files.retain(|path| {
+            let hidden = path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+
+            let known = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| {
+                    matches!(
+                        ext.to_ascii_lowercase().as_str(),
+                        "toml" | "yaml" | "yml" | "json"
+                    )
+                });
+
+            if hidden || !known || !path.is_file() {
+                return false;
+            }
+
+            let format = FileFormat::from_path(path);
+
+            if !format.is_enabled() {
+                warn!(
+                    "Skipping {}: {} support is not enabled",
+                    path.display(),
+                    format.name()
+                );
+            }
+
+            format.is_enabled()
+        });
+
+        files.sort();
+
+        if files.is_empty() {
+            if required {
+                error!("Config directory is empty (required): {}", dir.display());
+
+                return Err(ConfigError::EmptyDirectory(
+                    dir.to_str().unwrap_or_default().to_string(),
+                ));
+            }
+
+            warn!("Config directory is empty (optional): {}", dir.display());
+        }
+
+        for file in files {
+            self.load_file(&file, true, &[])?;
+        }
+
+        Ok(())
+    }
+
+    fn load_toml_str(&mut self, content: &str) -> Result<(), ConfigError> {
+        let (table, locations) = self
+            .parse(content, FileFormat::Toml)
+            .inspect_err(|e| error!("Failed to load TOML string: {e}"))?;
+
+        self.apply(table, ConfigSourceInfo::TomlString, &locations);
+
+        Ok(())
+    }
+
+    fn apply(&mut self, table: Table, info: ConfigSourceInfo, locations: &Locations) {
+        self.merge.apply(
+            &mut self.merged,
+            &mut self.provenance,
+            table,
+            &info,
+            locations,
+        );
+
+        self.sources.push(info);
+    }
+
+    /// Interpolates and parses the content of a text source, returning its table and key locations.
+    fn parse(&self, content: &str, format: FileFormat) -> Result<(Table, Locations), ConfigError> {
+        let locate = |text: &str| match format {
+            FileFormat::Toml => provenance::locate(text),
+            _ => Locations::new(),
+        };
+
+        match self.mode {
+            InterpolationMode::Raw => {
+                let interpolated = self
+                    .interpolator
+                    .interpolate(content)
+                    .map_err(ConfigError::interpolation_error)?;
+
+                Ok((format.parse(&interpolated)?, locate(&interpolated)))
+            }
+            InterpolationMode::Values => {
+                let mut table = format.parse(content)?;
+                self.interpolator
+                    .interpolate_table(&mut table)
+                    .map_err(ConfigError::interpolation_error)?;
+
+                Ok((table, locate(content)))
+            }
+        }
+    }
+
+    /// Takes the `key` include directive out of `table`, resolving its entries relative
+    /// to the directory of `path`. Glob patterns expand to the matching files, sorted,
+    /// never including the file itself.
+    fn take_includes(
+        table: &mut Table,
+        key: &str,
+        path: &Path,
+        canonical: &Path,
+    ) -> Result<Vec<PathBuf>, ConfigError> {
+        let entries = match table.remove(key) {
+            None => return Ok(Vec::new()),
+            Some(Value::String(entry)) => vec![entry],
+            Some(Value::Array(items)) => items
+                .into_iter()
+                .map(|item| match item {
+                    Value::String(entry) => Ok(entry),
+                    other => Err(other),
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|other| {
+                    ConfigError::parse_error(
+                        FileFormat::from_path(path),
+                        format!("'{key}' entries must be strings, found {other}"),
+                    )
+                })?,
+            Some(other) => {
+                return Err(ConfigError::parse_error(
+                    FileFormat::from_path(path),
+                    format!("'{key}' must be a string or an array, found {other}"),
+                ));
+            }
+        };
+
+        let base = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        let mut includes = Vec::new();
+
+        for entry in entries {
+            let include = base.join(&entry);
+
+            if glob::is_pattern(&entry) {
+                let pattern = include.to_string_lossy().replace('\\', "/");
+                let root = Self::glob_root(&include);
+                let mut matches = Vec::new();
+
+                // Without `**`, a pattern only reaches as deep as its own segments.
+                let depth = (!pattern.split('/').any(|segment| segment == "**"))
+                    .then(|| include.components().count() - root.components().count());
+
+                Self::find_matches(&root, &pattern, depth, &mut matches);
+                matches.retain(|found| {
+                    fs::canonicalize(found).map_or(true, |found| found != canonical)
+                });
+                matches.sort();
+
+                if matches.is_empty() {
+                    warn!(
+                        "Include pattern '{entry}' in {} matched no files",
+                        path.display()
+                    );
+                }
+
+                includes.extend(matches);
+            } else {
+                includes.push(include);
+            }
+        }
+
+        Ok(includes)
+    }
+
+    /// Returns the longest leading directory of a pattern without glob characters.
+    fn glob_root(pattern: &Path) -> PathBuf {
+        pattern
+            .components()
+            .take_while(|component| !glob::is_pattern(&component.as_os_str().to_string_lossy()))
+            .collect()
+    }
+
+    /// Collects the files under `dir` matching `pattern`, descending at most `depth`
+    /// levels (unbounded when `None`). Symlinked directories are not followed.
+    fn find_matches(dir: &Path, pattern: &str, depth: Option<usize>, matches: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_dir = entry.file_type().is_ok_and(|kind| kind.is_dir());
+
+            if is_dir {
+                if depth.is_none_or(|depth| depth > 1) {
+                    Self::find_matches(&path, pattern, depth.map(|depth| depth - 1), matches);
+                }
+            } else if path.is_file()
+                && glob::matches(pattern, &path.to_string_lossy().replace('\\', "/"), '/')
+            {
+                matches.push(path);
+            }
+        }
     }
 }
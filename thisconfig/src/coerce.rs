@@ -0,0 +1,221 @@
+use serde::de::{
+    self, DeserializeSeed, Deserializer, Expected, MapAccess, SeqAccess, Unexpected, Visitor,
+};
+use std::fmt;
+use toml::Value;
+
+/// Deserializer over a `toml::Value` that parses strings into numbers and
+/// booleans when the target type asks for one, and renders numbers and booleans
+/// when it asks for a string.
+///
+/// Used for every section, since placeholders expanded in `InterpolationMode::Values`
+/// (`port = "${PORT}"`) leave scalars as strings and environment overlays type any
+/// value that looks like a number, including a `123456` password.
+pub(crate) struct Coerce(pub(crate) Value);
+
+/// Error of [`Coerce`], keeping the type serde expected apart from the message.
+#[derive(Debug)]
+pub(crate) struct Error {
+    pub(crate) message: String,
+    /// Set when the value had the wrong type or an invalid value, e.g. `u16`.
+    pub(crate) expected: Option<String>,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        Self {
+            message: message.to_string(),
+            expected: None,
+        }
+    }
+
+    fn invalid_type(unexpected: Unexpected<'_>, expected: &dyn Expected) -> Self {
+        Self {
+            message: format!("invalid type: {unexpected}, expected {expected}"),
+            expected: Some(expected.to_string()),
+        }
+    }
+
+    fn invalid_value(unexpected: Unexpected<'_>, expected: &dyn Expected) -> Self {
+        Self {
+            message: format!("invalid value: {unexpected}, expected {expected}"),
+            expected: Some(expected.to_string()),
+        }
+    }
+}
+
+macro_rules! coerce_number {
+    ($($method:ident => $ty:ty, $visit:ident;)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                match self.0 {
+                    Value::String(s) => match s.trim().parse::<$ty>() {
+                        Ok(n) => visitor.$visit(n),
+                        Err(_) => visitor.visit_string(s),
+                    },
+                    other => Coerce(other).deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Coerce {
+    type Error = Error;
+
+    /// Visits scalars directly, so type errors are raised with [`Error`] and keep `expected`.
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::String(s) => visitor.visit_string(s),
+            Value::Integer(i) => visitor.visit_i64(i),
+            Value::Float(f) => visitor.visit_f64(f),
+            Value::Boolean(b) => visitor.visit_bool(b),
+            Value::Array(items) => visitor.visit_seq(Seq(items.into_iter())),
+            Value::Table(table) => visitor.visit_map(Map {
+                entries: table.into_iter(),
+                value: None,
+            }),
+            datetime @ Value::Datetime(_) => {
+                datetime.deserialize_any(visitor).map_err(de::Error::custom)
+            }
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::String(s) => match s.trim().parse::<bool>() {
+                Ok(b) => visitor.visit_bool(b),
+                Err(_) => visitor.visit_string(s),
+            },
+            other => Coerce(other).deserialize_any(visitor),
+        }
+    }
+
+    coerce_number! {
+        deserialize_i8 => i64, visit_i64;
+        deserialize_i16 => i64, visit_i64;
+        deserialize_i32 => i64, visit_i64;
+        deserialize_i64 => i64, visit_i64;
+        deserialize_i128 => i128, visit_i128;
+        deserialize_u8 => u64, visit_u64;
+        deserialize_u16 => u64, visit_u64;
+        deserialize_u32 => u64, visit_u64;
+        deserialize_u64 => u64, visit_u64;
+        deserialize_u128 => u128, visit_u128;
+        deserialize_f32 => f64, visit_f64;
+        deserialize_f64 => f64, visit_f64;
+    }
+
+    /// TOML has no null, so a present value is always `Some`.
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.0
+            .deserialize_enum(name, variants, visitor)
+            .map_err(de::Error::custom)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.0 {
+            // Datetimes are exposed by toml as a private struct.
+            value @ Value::Datetime(_) => value
+                .deserialize_struct(name, fields, visitor)
+                .map_err(de::Error::custom),
+            other => Coerce(other).deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Integer(i) => visitor.visit_string(i.to_string()),
+            Value::Float(f) => visitor.visit_string(f.to_string()),
+            Value::Boolean(b) => visitor.visit_string(b.to_string()),
+            other => Coerce(other).deserialize_any(visitor),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        char bytes byte_buf unit unit_struct seq tuple tuple_struct map identifier
+        ignored_any
+    }
+}
+
+struct Seq(std::vec::IntoIter<Value>);
+
+impl<'de> SeqAccess<'de> for Seq {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        self.0
+            .next()
+            .map(|value| seed.deserialize(Coerce(value)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+struct Map {
+    entries: toml::map::IntoIter<String, Value>,
+    value: Option<Value>,
+}
+
+impl<'de> MapAccess<'de> for Map {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        let Some((key, value)) = self.entries.next() else {
+            return Ok(None);
+        };
+
+        self.value = Some(value);
+        seed.deserialize(Coerce(Value::String(key))).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        match self.value.take() {
+            Some(value) => seed.deserialize(Coerce(value)),
+            None => Err(de::Error::custom("value requested before key")),
+        }
+    }
+}
@@ -1,14 +1,25 @@
-#[cfg(feature = "validation")]
-use crate::ConfigError;
-use crate::{ConfigBuilder, ConfigItem};
-use serde::de::{DeserializeOwned, IntoDeserializer};
-use std::{path::Path, path::PathBuf, sync::Arc};
+use crate::{
+    ConfigBuilder, ConfigError, ConfigItem, FileFormat, KeyOrigin, Redaction, ShadowedValue,
+    coerce::Coerce, provenance::Provenance,
+};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_path_to_error::Segment;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt,
+    path::Path,
+    path::PathBuf,
+    sync::{Arc, PoisonError, RwLock},
+    time::SystemTime,
+};
 use toml::{Table, Value};
 
 #[cfg(feature = "validation")]
 use validator::Validate;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
 pub enum ConfigSourceInfo {
     File {
         path: PathBuf,
@@ -16,12 +27,92 @@ pub enum ConfigSourceInfo {
         found: bool,
     },
     TomlString,
+    Environment {
+        prefix: String,
+        separator: String,
+    },
+    /// Overrides added with `add_overrides` or `add_args`.
+    CommandLine,
+}
+
+/// Walks nested tables of `table` along a dotted key path. An exact top-level key wins.
+pub(crate) fn lookup<'a>(table: &'a Table, path: &str) -> Option<&'a Value> {
+    if let Some(value) = table.get(path) {
+        return Some(value);
+    }
+
+    let mut segments = path.split('.');
+    let mut current = table.get(segments.next()?)?;
+
+    for segment in segments {
+        current = current.as_table()?.get(segment)?;
+    }
+
+    Some(current)
+}
+
+impl fmt::Display for ConfigSourceInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File { path, .. } => write!(f, "{}", path.display()),
+            Self::TomlString => write!(f, "TOML string"),
+            Self::Environment { prefix, separator } => {
+                write!(f, "environment ({prefix}{separator}*)")
+            }
+            Self::CommandLine => write!(f, "command line"),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct Config {
     pub(crate) inner: Arc<Table>,
     pub(crate) sources: Arc<[ConfigSourceInfo]>,
+    /// Files and directories read or probed during build, polled by hot reload.
+    #[cfg(feature = "reload")]
+    pub(crate) watched: Arc<[PathBuf]>,
+    pub(crate) provenance: Arc<Provenance>,
+    pub(crate) profile: Option<Arc<str>>,
+    pub(crate) loaded_at: Option<SystemTime>,
+    pub(crate) cache: Arc<SectionCache>,
+}
+
+/// Deserialized sections keyed by their `ConfigItem` type.
+#[derive(Default)]
+pub(crate) struct SectionCache {
+    sections: RwLock<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+}
+
+impl SectionCache {
+    fn get<T: ConfigItem>(&self) -> Option<Arc<T>> {
+        let sections = self.sections.read().unwrap_or_else(PoisonError::into_inner);
+        let item = sections.get(&TypeId::of::<T>())?.clone();
+
+        item.downcast::<T>().ok()
+    }
+
+    /// Stores `item` unless another thread cached the section first, returning the cached value.
+    fn insert<T: ConfigItem>(&self, item: Arc<T>) -> Arc<T> {
+        let mut sections = self
+            .sections
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        let cached = sections.entry(TypeId::of::<T>()).or_insert(item).clone();
+
+        cached
+            .downcast::<T>()
+            .expect("section cache entries match their TypeId")
+    }
+}
+
+impl fmt::Debug for SectionCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sections = self.sections.read().unwrap_or_else(PoisonError::into_inner);
+        f.debug_struct("SectionCache")
+            .field("sections", &sections.len())
+            .finish()
+    }
 }
 
 impl Config {
@@ -34,6 +125,16 @@ impl Config {
         &self.sources
     }
 
+    /// Returns the profile used for `add_profiled_file` layers, if any.
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// Time at which the configuration was built, `None` for `Config::default()`.
+    pub fn loaded_at(&self) -> Option<SystemTime> {
+        self.loaded_at
+    }
+
     /// Iterates only existing file sources used during build.
     pub fn file_sources(&self) -> impl Iterator<Item = &Path> {
         self.sources.iter().filter_map(|source| match source {
@@ -47,18 +148,161 @@ impl Config {
         self.file_sources().next()
     }
 
+    /// Returns the source that supplied the final value of a dotted key path,
+    /// e.g. `"database.port"`. Only leaf values (not tables) are tracked.
+    pub fn origin(&self, key: &str) -> Option<&KeyOrigin> {
+        self.provenance.origin(key)
+    }
+
+    /// Returns every source that contributed to the value of a dotted key path, in
+    /// merge order. Only arrays combined with a strategy other than
+    /// `ArrayMerge::Replace` have more than one; the last entry is `origin(key)`.
+    pub fn origins(&self, key: &str) -> Vec<&KeyOrigin> {
+        self.provenance.origins(key)
+    }
+
+    /// Returns every value that was overwritten or unset by a later source, in merge order.
+    pub fn shadowed(&self) -> &[ShadowedValue] {
+        self.provenance.shadowed()
+    }
+
     /// Retrieves a configuration section.
     ///
     /// # Returns
     ///
     /// `Some(T)` if found, `None` otherwise.
     pub fn get<T: DeserializeOwned + ConfigItem>(&self) -> Option<T> {
+        self.try_get::<T>().ok()
+    }
+
+    /// Retrieves a configuration section, reporting why it could not be loaded.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::KeyNotFound` if the section is missing, or
+    /// `ConfigError::SectionError` with the field path, expected type and source
+    /// location if it cannot be deserialized.
+    pub fn try_get<T: DeserializeOwned + ConfigItem>(&self) -> Result<T, ConfigError> {
+        self.try_get_shared::<T>().map(|item| T::clone(&item))
+    }
+
+    /// Retrieves a configuration section shared behind an `Arc`.
+    ///
+    /// Sections are deserialized on first access and cached per type, so later
+    /// calls (including `get` and `try_get`) skip deserialization.
+    ///
+    /// # Returns
+    ///
+    /// `Some(Arc<T>)` if found, `None` otherwise.
+    pub fn get_shared<T: ConfigItem>(&self) -> Option<Arc<T>> {
+        self.try_get_shared::<T>().ok()
+    }
+
+    /// Like `get_shared`, but reports why the section could not be loaded.
+    ///
+    /// # Errors
+    ///
+    /// Same as `try_get`.
+    pub fn try_get_shared<T: ConfigItem>(&self) -> Result<Arc<T>, ConfigError> {
+        if let Some(item) = self.cache.get::<T>() {
+            return Ok(item);
+        }
+
         let key = T::key();
 
-        let item = self.inner.get(key).cloned()?;
-        let value = Value::into_deserializer(item);
+        let item = self
+            .lookup(key)
+            .cloned()
+            .ok_or_else(|| ConfigError::key_not_found(key))?;
+
+        let item = Arc::new(self.deserialize_section::<T>(key, item)?);
+
+        Ok(self.cache.insert(item))
+    }
+
+    /// Retrieves any deserializable value at a dotted key path, e.g.
+    /// `"services.billing.db.port"`.
+    ///
+    /// # Returns
+    ///
+    /// `Some(T)` if found, `None` otherwise.
+    pub fn get_path<T: DeserializeOwned>(&self, path: &str) -> Option<T> {
+        let item = self.lookup(path).cloned()?;
+
+        T::deserialize(Coerce(item)).ok()
+    }
+
+    pub(crate) fn deserialize_section<T: DeserializeOwned>(
+        &self,
+        key: &str,
+        item: Value,
+    ) -> Result<T, ConfigError> {
+        serde_path_to_error::deserialize(Coerce(item)).map_err(|e| {
+            let mut path = key.to_string();
+
+            for segment in e.path().iter() {
+                match segment {
+                    Segment::Map { key } => path = format!("{path}.{key}"),
+                    _ => break,
+                }
+            }
+
+            let field = e.path().iter().next().map(|_| e.path().to_string());
+            let crate::coerce::Error { message, expected } = e.into_inner();
+
+            let origin = std::iter::successors(Some(path.as_str()), |p| {
+                p.rsplit_once('.').map(|(parent, _)| parent)
+            })
+            .find_map(|p| self.origin(p))
+            .cloned()
+            .map(Box::new);
+
+            ConfigError::SectionError {
+                key: key.to_string(),
+                field,
+                expected,
+                message,
+                origin,
+            }
+        })
+    }
+
+    /// Walks nested tables along a dotted key path. An exact top-level key wins
+    /// over the nested interpretation.
+    pub(crate) fn lookup(&self, path: &str) -> Option<&Value> {
+        lookup(&self.inner, path)
+    }
+
+    /// Returns the effective merged configuration with redacted keys replaced by `"***"`.
+    pub fn to_table(&self, redaction: &Redaction) -> Table {
+        redaction.apply(&self.inner)
+    }
+
+    /// Serializes the effective merged configuration as TOML.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::SerializeError` if the table cannot be written as TOML.
+    pub fn to_toml_string(&self, redaction: &Redaction) -> Result<String, ConfigError> {
+        toml::to_string(&self.to_table(redaction))
+            .map_err(|e| ConfigError::serialize_error(FileFormat::Toml, e))
+    }
+
+    #[cfg(feature = "json")]
+    /// Serializes the effective merged configuration as pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::SerializeError` if the JSON cannot be written.
+    pub fn to_json(&self, redaction: &Redaction) -> Result<String, ConfigError> {
+        serde_json::to_string_pretty(&self.to_json_value(redaction))
+            .map_err(|e| ConfigError::serialize_error(FileFormat::Json, e))
+    }
 
-        T::deserialize(value).ok()
+    #[cfg(feature = "json")]
+    /// Returns the effective merged configuration as a JSON value, with datetimes as strings.
+    pub fn to_json_value(&self, redaction: &Redaction) -> serde_json::Value {
+        crate::format::json::from_toml(&Value::Table(self.to_table(redaction)))
     }
 
     #[cfg(feature = "validation")]
@@ -72,37 +316,24 @@ impl Config {
         T: DeserializeOwned + ConfigItem + Validate,
     {
         let key = T::key();
+        let item = self.try_get_shared::<T>()?;
 
-        let item = self
-            .inner
-            .get(key)
-            .cloned()
-            .ok_or_else(|| ConfigError::KeyNotFound {
-                key: key.to_string(),
-            })?;
-
-        let value = Value::into_deserializer(item);
-
-        let deserialized: T = T::deserialize(value)?;
+        item.validate().map_err(|e| ConfigError::ValidationError {
+            message: format!("Validation failed for '{key}': {e}"),
+        })?;
 
-        deserialized
-            .validate()
-            .map_err(|e| ConfigError::ValidationError {
-                message: format!("Validation failed for '{key}': {e}"),
-            })?;
-
-        Ok(deserialized)
+        Ok(T::clone(&item))
     }
 
     /// Retrieves a required configuration section, panicking if not found or invalid.
     ///
     /// # Panics
-    /// Panics if the configuration section is missing or cannot be deserialized. Recommended for
-    /// critical configuration items that must be present for the application to function. For optional
-    /// items, use `get` or `get_or_default` instead.
+    /// Panics with the `try_get` error if the configuration section is missing or cannot be
+    /// deserialized. Recommended for critical configuration items that must be present for the
+    /// application to function. For optional items, use `get` or `get_or_default` instead.
     pub fn expect<T: DeserializeOwned + ConfigItem>(&self) -> T {
-        self.get::<T>()
-            .unwrap_or_else(|| panic!("Failed to load configuration for key '{}'", T::key()))
+        self.try_get::<T>()
+            .unwrap_or_else(|e| panic!("Failed to load configuration for key '{}': {e}", T::key()))
     }
 
     /// Retrieves a configuration section, returning default if not found or invalid.
@@ -118,7 +349,7 @@ impl Config {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{ConfigBuilder, ConfigError};
+    use crate::{ConfigBuilder, ConfigError, InterpolationMode};
     use serde::Deserialize;
     use std::fs;
 
@@ -463,4 +694,1139 @@ key2 = "value2"
 
         assert_eq!(config.primary_file_source(), Some(path1.as_path()));
     }
+
+    #[test]
+    fn test_builder_env_prefix_overlay() {
+        unsafe {
+            std::env::set_var("ENVOVERLAY__TEST__PORT", "5432");
+            std::env::set_var("ENVOVERLAY__TEST__NAME", "from_env");
+        }
+
+        let config = Config::builder()
+            .add_toml_str("[test]\nname = \"file\"\nport = 80")
+            .add_env_prefix("ENVOVERLAY", "__")
+            .build()
+            .expect("failed to build config");
+
+        let test_config = config
+            .get::<TestConfig>()
+            .expect("failed to get test config");
+
+        assert_eq!(test_config.name, "from_env");
+        assert_eq!(test_config.port, 5432);
+        assert!(matches!(
+            &config.sources()[1],
+            ConfigSourceInfo::Environment { prefix, separator }
+                if prefix == "ENVOVERLAY" && separator == "__"
+        ));
+    }
+
+    #[test]
+    fn test_builder_env_prefix_keeps_strings() {
+        #[derive(Debug, Clone, Deserialize)]
+        struct Database {
+            password: String,
+            version: String,
+            port: u16,
+            replicas: Vec<String>,
+        }
+
+        impl ConfigItem for Database {
+            fn key() -> &'static str {
+                "db"
+            }
+        }
+
+        unsafe {
+            std::env::set_var("ENVSTRINGS__DB__PASSWORD", "123456");
+            std::env::set_var("ENVSTRINGS__DB__VERSION", "1.10");
+            std::env::set_var("ENVSTRINGS__DB__PORT", "5433");
+            std::env::set_var("ENVSTRINGS__DB__REPLICAS", "[\"a\", \"b\"]");
+        }
+
+        let config = Config::builder()
+            .add_env_prefix("ENVSTRINGS", "__")
+            .build()
+            .expect("failed to build config");
+
+        let db = config
+            .try_get::<Database>()
+            .expect("failed to get db config");
+
+        assert_eq!(db.password, "123456");
+        assert_eq!(db.version, "1.10");
+        assert_eq!(db.port, 5433);
+        assert_eq!(db.replicas, vec!["a", "b"]);
+        assert_eq!(config.get_path::<u32>("db.password"), Some(123456));
+    }
+
+    #[test]
+    fn test_builder_env_prefix_types_buffered_values() {
+        #[derive(Debug, Clone, Deserialize)]
+        struct Pool {
+            port: u16,
+            tls: bool,
+            password: String,
+        }
+
+        #[derive(Debug, Clone, Deserialize)]
+        struct Database {
+            version: String,
+            #[serde(flatten)]
+            pool: Pool,
+        }
+
+        impl ConfigItem for Database {
+            fn key() -> &'static str {
+                "db"
+            }
+        }
+
+        unsafe {
+            std::env::set_var("ENVBUFFERED__DB__PORT", "5432");
+            std::env::set_var("ENVBUFFERED__DB__TLS", "true");
+            std::env::set_var("ENVBUFFERED__DB__VERSION", "1.10");
+            std::env::set_var("ENVBUFFERED__DB__PASSWORD", "\"123456\"");
+        }
+
+        let config = Config::builder()
+            .add_env_prefix("ENVBUFFERED", "__")
+            .build()
+            .expect("failed to build config");
+
+        let db = config
+            .try_get::<Database>()
+            .expect("failed to get db config");
+
+        assert_eq!(db.pool.port, 5432);
+        assert!(db.pool.tls);
+        assert_eq!(db.pool.password, "123456");
+        assert_eq!(db.version, "1.10");
+
+        let exported = config
+            .to_toml_string(&Redaction::new())
+            .expect("failed to export");
+
+        assert!(exported.contains("port = 5432"));
+        assert!(exported.contains("version = \"1.10\""));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_builder_env_prefix_skips_non_utf8_variables() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let invalid = OsStr::from_bytes(b"\xff");
+
+        unsafe {
+            std::env::set_var("ENVBYTES_UNRELATED", invalid);
+            std::env::set_var("ENVBYTES__TEST__NAME", invalid);
+            std::env::set_var("ENVBYTES__TEST__PORT", "5432");
+        }
+
+        let config = Config::builder().add_env_prefix("ENVBYTES", "__").build();
+
+        unsafe {
+            std::env::remove_var("ENVBYTES_UNRELATED");
+            std::env::remove_var("ENVBYTES__TEST__NAME");
+        }
+
+        let config = config.expect("failed to build config");
+
+        assert_eq!(config.get_path::<u16>("test.port"), Some(5432));
+        assert!(config.get_path::<String>("test.name").is_none());
+    }
+
+    #[test]
+    fn test_builder_env_prefix_respects_order() {
+        unsafe { std::env::set_var("ENVORDER__TEST__PORT", "1111") };
+
+        let config = Config::builder()
+            .add_env_prefix("ENVORDER", "__")
+            .add_toml_str("[test]\nname = \"file\"\nport = 2222")
+            .build()
+            .expect("failed to build config");
+
+        let test_config = config
+            .get::<TestConfig>()
+            .expect("failed to get test config");
+
+        assert_eq!(test_config.port, 2222);
+    }
+
+    #[test]
+    fn test_origin_tracks_winning_source() {
+        let temp_file = tempfile::NamedTempFile::new().expect("failed to create temp file");
+        let path = temp_file.path().to_path_buf();
+        fs::write(&path, "[test]\nname = \"file\"\nport = 8080").expect("failed to write");
+
+        let config = Config::builder()
+            .add_file(&path)
+            .add_toml_str("[test]\n\nport = 9090")
+            .build()
+            .expect("failed to build config");
+
+        let name = config.origin("test.name").expect("missing origin");
+        assert!(matches!(&name.source, ConfigSourceInfo::File { path: p, .. } if p == &path));
+        assert_eq!(name.location, Some(crate::Location { line: 2, column: 1 }));
+
+        let port = config.origin("test.port").expect("missing origin");
+        assert_eq!(port.source, ConfigSourceInfo::TomlString);
+        assert_eq!(port.location, Some(crate::Location { line: 3, column: 1 }));
+
+        assert!(config.origin("test.missing").is_none());
+    }
+
+    #[test]
+    fn test_shadowed_values_listed() {
+        let config = Config::builder()
+            .add_toml_str("[test]\nname = \"first\"\nport = 1")
+            .add_toml_str("[test]\nname = \"second\"")
+            .add_toml_str("test = 3")
+            .build()
+            .expect("failed to build config");
+
+        let shadowed = config
+            .shadowed()
+            .iter()
+            .map(|s| (s.key.as_str(), s.value.clone()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            shadowed,
+            vec![
+                ("test.name", Value::String("first".into())),
+                ("test.name", Value::String("second".into())),
+                ("test.port", Value::Integer(1)),
+            ]
+        );
+        assert!(config.origin("test").is_some());
+        assert!(config.origin("test.port").is_none());
+    }
+
+    #[cfg(all(feature = "yaml", feature = "json"))]
+    #[test]
+    fn test_builder_merges_yaml_and_json_files() {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        let yaml = dir.path().join("config.yaml");
+        let json = dir.path().join("config.json");
+        fs::write(&yaml, "test:\n  name: yaml\n  port: 8080\n").expect("failed to write");
+        fs::write(&json, r#"{"test": {"port": 9090}}"#).expect("failed to write");
+
+        let config = Config::builder()
+            .add_required_file(&yaml)
+            .add_required_file(&json)
+            .build()
+            .expect("failed to build config");
+
+        let test_config = config
+            .get::<TestConfig>()
+            .expect("failed to get test config");
+
+        assert_eq!(test_config.name, "yaml");
+        assert_eq!(test_config.port, 9090);
+        assert!(
+            config
+                .origin("test.port")
+                .is_some_and(|o| o.location.is_none())
+        );
+    }
+
+    #[test]
+    fn test_get_nested_section_by_dotted_key() {
+        #[derive(Debug, Clone, Deserialize)]
+        struct BillingDb {
+            port: u16,
+        }
+
+        impl ConfigItem for BillingDb {
+            fn key() -> &'static str {
+                "services.billing.db"
+            }
+        }
+
+        let config = Config::builder()
+            .add_toml_str("[services.billing.db]\nport = 5433\n\n[services.auth]\nport = 1")
+            .build()
+            .expect("failed to build config");
+
+        assert_eq!(config.get::<BillingDb>().map(|db| db.port), Some(5433));
+        assert_eq!(
+            config.get_path::<u16>("services.billing.db.port"),
+            Some(5433)
+        );
+        assert_eq!(config.get_path::<u16>("services.auth.port"), Some(1));
+        assert_eq!(config.get_path::<u16>("services.billing.db.missing"), None);
+        assert_eq!(config.get_path::<u16>("services.auth.port.nope"), None);
+    }
+
+    #[test]
+    fn test_try_get_reports_field_type_and_location() {
+        let temp_file = tempfile::NamedTempFile::new().expect("failed to create temp file");
+        let path = temp_file.path().to_path_buf();
+        fs::write(&path, "[test]\nname = \"app\"\nport = \"eighty\"").expect("failed to write");
+
+        let config = Config::builder()
+            .add_file(&path)
+            .build()
+            .expect("failed to build config");
+
+        let err = config
+            .try_get::<TestConfig>()
+            .expect_err("port should be invalid");
+
+        let ConfigError::SectionError {
+            key,
+            field,
+            expected,
+            origin,
+            ..
+        } = &err
+        else {
+            panic!("unexpected error: {err}");
+        };
+
+        assert_eq!(key, "test");
+        assert_eq!(field.as_deref(), Some("port"));
+        assert_eq!(expected.as_deref(), Some("u16"));
+        assert_eq!(
+            origin.as_ref().and_then(|o| o.location),
+            Some(crate::Location { line: 3, column: 1 })
+        );
+        assert!(err.to_string().contains(&format!("{}:3:1", path.display())));
+
+        let config = Config::builder()
+            .add_toml_str("[test]\nname = \"app\"\nport = 70000")
+            .build()
+            .expect("failed to build config");
+
+        assert!(matches!(
+            config.try_get::<TestConfig>(),
+            Err(ConfigError::SectionError { expected: Some(ref expected), .. }) if expected == "u16"
+        ));
+    }
+
+    #[test]
+    fn test_try_get_missing_key() {
+        let config = Config::builder()
+            .add_toml_str("[other]\nvalue = 1")
+            .build()
+            .expect("failed to build config");
+
+        assert!(matches!(
+            config.try_get::<TestConfig>(),
+            Err(ConfigError::KeyNotFound { key }) if key == "test"
+        ));
+    }
+
+    #[test]
+    fn test_profiled_file_layers() {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        let base = dir.path().join("config.toml");
+        fs::write(&base, "[test]\nname = \"base\"\nport = 1").expect("failed to write");
+        fs::write(
+            dir.path().join("config.production.toml"),
+            "[test]\nport = 2",
+        )
+        .expect("failed to write");
+        fs::write(dir.path().join("config.staging.toml"), "[test]\nport = 3")
+            .expect("failed to write");
+        fs::write(
+            dir.path().join("config.local.toml"),
+            "[test]\nname = \"local\"",
+        )
+        .expect("failed to write");
+
+        let config = Config::builder()
+            .with_profile("production")
+            .add_profiled_file(&base)
+            .build()
+            .expect("failed to build config");
+
+        let test_config = config
+            .get::<TestConfig>()
+            .expect("failed to get test config");
+
+        assert_eq!(test_config.name, "local");
+        assert_eq!(test_config.port, 2);
+        assert_eq!(config.profile(), Some("production"));
+
+        let files: Vec<&Path> = config.file_sources().collect();
+        assert_eq!(
+            files,
+            vec![
+                base.as_path(),
+                dir.path().join("config.production.toml").as_path(),
+                dir.path().join("config.local.toml").as_path(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_profiled_file_requires_base() {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+
+        let result = Config::builder()
+            .with_profile("production")
+            .add_profiled_file(dir.path().join("config.toml"))
+            .build();
+
+        assert!(matches!(result, Err(ConfigError::FileNotFound(_))));
+    }
+
+    #[test]
+    fn test_secret_is_redacted() {
+        use crate::Secret;
+
+        #[derive(Debug, Clone, Deserialize, serde::Serialize)]
+        struct DbConfig {
+            user: String,
+            password: Secret<String>,
+        }
+
+        impl ConfigItem for DbConfig {
+            fn key() -> &'static str {
+                "db"
+            }
+        }
+
+        unsafe { std::env::set_var("SECRET_TEST_PASSWORD", "hunter2") };
+
+        let config = Config::builder()
+            .add_toml_str("[db]\nuser = \"admin\"\npassword = \"${SECRET_TEST_PASSWORD}\"")
+            .build()
+            .expect("failed to build config");
+
+        let db = config.expect::<DbConfig>();
+
+        assert_eq!(db.password.expose(), "hunter2");
+        assert_eq!(db.password.to_string(), "***");
+        assert!(!format!("{db:?}").contains("hunter2"));
+        assert_eq!(
+            toml::to_string(&db).expect("failed to serialize"),
+            "user = \"admin\"\npassword = \"***\"\n"
+        );
+    }
+
+    #[test]
+    fn test_require_fails_fast_with_every_bad_section() {
+        #[derive(Debug, Clone, Deserialize)]
+        struct OtherConfig {
+            #[allow(dead_code)]
+            enabled: bool,
+        }
+
+        impl ConfigItem for OtherConfig {
+            fn key() -> &'static str {
+                "other"
+            }
+        }
+
+        let result = Config::builder()
+            .add_toml_str("[test]\nname = \"app\"\nport = \"eighty\"")
+            .require::<TestConfig>()
+            .require::<OtherConfig>()
+            .build();
+
+        let Err(ConfigError::InvalidSections { errors }) = result else {
+            panic!("expected InvalidSections");
+        };
+
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(&errors[0], ConfigError::SectionError { key, .. } if key == "test"));
+        assert!(matches!(&errors[1], ConfigError::KeyNotFound { key } if key == "other"));
+    }
+
+    #[test]
+    fn test_require_passes_for_valid_sections() {
+        let config = Config::builder()
+            .add_toml_str("[test]\nname = \"app\"\nport = 80")
+            .require::<TestConfig>()
+            .build();
+
+        assert!(config.is_ok());
+    }
+
+    #[cfg(feature = "validation")]
+    #[test]
+    fn test_require_validated_runs_validation() {
+        use validator::Validate;
+
+        #[derive(Debug, Clone, Deserialize, Validate)]
+        struct RangeConfig {
+            #[validate(range(min = 1024))]
+            port: u16,
+        }
+
+        impl ConfigItem for RangeConfig {
+            fn key() -> &'static str {
+                "range"
+            }
+        }
+
+        let result = Config::builder()
+            .add_toml_str("[range]\nport = 80")
+            .require_validated::<RangeConfig>()
+            .build();
+
+        let Err(ConfigError::InvalidSections { errors }) = result else {
+            panic!("expected InvalidSections");
+        };
+
+        assert!(matches!(&errors[0], ConfigError::ValidationError { .. }));
+    }
+
+    #[test]
+    fn test_get_shared_caches_section() {
+        let config = Config::builder()
+            .add_toml_str("[test]\nname = \"cached\"\nport = 80")
+            .build()
+            .expect("failed to build config");
+
+        let first = config.get_shared::<TestConfig>().expect("missing section");
+        let second = config
+            .clone()
+            .get_shared::<TestConfig>()
+            .expect("missing section");
+
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(config.get::<TestConfig>().as_ref(), Some(&*first));
+        assert!(config.get_shared::<TestConfig>().is_some());
+    }
+
+    #[test]
+    fn test_values_interpolation_coerces_and_prevents_injection() {
+        #[derive(Debug, Clone, Deserialize)]
+        struct ListenConfig {
+            host: String,
+            port: u16,
+            debug: Option<bool>,
+            ports: Vec<u16>,
+        }
+
+        impl ConfigItem for ListenConfig {
+            fn key() -> &'static str {
+                "listen"
+            }
+        }
+
+        unsafe { std::env::set_var("VALUES_TEST_PORT", "8443") };
+        unsafe { std::env::set_var("VALUES_TEST_HOST", "evil\"\n[admin]\nenabled = true\n#") };
+
+        let config = Config::builder()
+            .with_interpolation_mode(InterpolationMode::Values)
+            .add_toml_str(
+                "[listen]\n\
+                 host = \"${VALUES_TEST_HOST}\"\n\
+                 port = \"${VALUES_TEST_PORT}\"\n\
+                 debug = \"${VALUES_TEST_DEBUG:-true}\"\n\
+                 ports = [\"${VALUES_TEST_PORT}\", 9000]\n\
+                 # port = ${NOT_EXPANDED_IN_COMMENTS}",
+            )
+            .build()
+            .expect("failed to build config");
+
+        let listen = config.expect::<ListenConfig>();
+
+        assert_eq!(listen.port, 8443);
+        assert_eq!(listen.debug, Some(true));
+        assert_eq!(listen.ports, vec![8443, 9000]);
+        assert!(listen.host.contains("[admin]"));
+        assert!(config.get_path::<Table>("admin").is_none());
+        assert_eq!(config.get_path::<u16>("listen.port"), Some(8443));
+    }
+
+    #[test]
+    fn test_values_interpolation_reports_invalid_number() {
+        #[derive(Debug, Clone, Deserialize)]
+        struct ListenConfig {
+            #[allow(dead_code)]
+            port: u16,
+        }
+
+        impl ConfigItem for ListenConfig {
+            fn key() -> &'static str {
+                "listen"
+            }
+        }
+
+        let config = Config::builder()
+            .with_interpolation_mode(InterpolationMode::Values)
+            .add_toml_str("[listen]\nport = \"${VALUES_TEST_BAD_PORT:-eighty}\"")
+            .build()
+            .expect("failed to build config");
+
+        let error = config.try_get::<ListenConfig>().unwrap_err();
+        assert!(
+            matches!(error, ConfigError::SectionError { field: Some(ref f), .. } if f == "port")
+        );
+    }
+
+    #[test]
+    fn test_custom_resolver_on_builder() {
+        #[derive(Debug, Clone, Deserialize)]
+        struct DbConfig {
+            user: String,
+            password: crate::Secret<String>,
+        }
+
+        impl ConfigItem for DbConfig {
+            fn key() -> &'static str {
+                "db"
+            }
+        }
+
+        let vault = HashMap::from([("db/creds#password", "s3cret"), ("db/creds#user", "app")]);
+
+        let config = Config::builder()
+            .add_resolver("vault", move |path: &str| {
+                Ok(vault.get(path).map(ToString::to_string))
+            })
+            .add_toml_str(
+                "[db]\nuser = \"${vault:db/creds#user}\"\npassword = \"${vault:db/creds#password}\"",
+            )
+            .build()
+            .expect("failed to build config");
+
+        let db = config.expect::<DbConfig>();
+        assert_eq!(db.user, "app");
+        assert_eq!(db.password.expose(), "s3cret");
+
+        let error = Config::builder()
+            .add_resolver("vault", |_: &str| Ok(None))
+            .add_toml_str("[db]\nuser = \"${vault:missing}\"")
+            .build()
+            .unwrap_err();
+
+        assert!(matches!(error, ConfigError::InterpolationError { .. }));
+    }
+
+    #[test]
+    fn test_config_references_use_overridden_values() {
+        let config = Config::builder()
+            .add_toml_str(
+                "[server]\nhost = \"localhost\"\nport = 8080\n\n\
+                 [client]\nbase_url = \"http://${config:server.host}:${config:server.port}\"",
+            )
+            .add_toml_str("[server]\nhost = \"example.com\"")
+            .build()
+            .expect("failed to build config");
+
+        assert_eq!(
+            config.get_path::<String>("client.base_url").as_deref(),
+            Some("http://example.com:8080")
+        );
+
+        let error = Config::builder()
+            .add_toml_str("[a]\nx = \"${config:b.y}\"\n[b]\ny = \"${config:a.x}\"")
+            .build()
+            .unwrap_err();
+
+        assert!(error.to_string().contains("cyclic config reference"));
+    }
+
+    #[test]
+    fn test_config_references_skip_uninterpolated_sources() {
+        unsafe {
+            std::env::set_var("ENVLITERAL__DB__PASSWORD", "pa$${word}");
+            std::env::set_var("ENVLITERAL__DB__NOTE", "${config:unterminated");
+        }
+
+        let config = Config::builder()
+            .add_toml_str("[db]\nuser = \"admin\"\nlabel = \"${config:db.user}\"")
+            .add_env_prefix("ENVLITERAL", "__")
+            .add_overrides([("db.alias", "$${config:db.user}")])
+            .build()
+            .expect("failed to build config");
+
+        assert_eq!(
+            config.get_path::<String>("db.password").as_deref(),
+            Some("pa$${word}")
+        );
+        assert_eq!(
+            config.get_path::<String>("db.note").as_deref(),
+            Some("${config:unterminated")
+        );
+        assert_eq!(
+            config.get_path::<String>("db.alias").as_deref(),
+            Some("$${config:db.user}")
+        );
+        assert_eq!(
+            config.get_path::<String>("db.label").as_deref(),
+            Some("admin")
+        );
+    }
+
+    #[test]
+    fn test_to_toml_string_redacts_keys_and_secrets() {
+        use crate::{
+            Redaction,
+            schema::{FieldSchema, SectionSchema},
+        };
+
+        #[derive(Debug, Clone, Deserialize)]
+        struct DbConfig;
+
+        impl ConfigItem for DbConfig {
+            fn key() -> &'static str {
+                "db"
+            }
+
+            fn schema() -> SectionSchema {
+                let field = |name: &str, secret, fields| FieldSchema {
+                    name: name.to_string(),
+                    ty: "Dsn",
+                    doc: None,
+                    required: true,
+                    default: None,
+                    constraints: vec![],
+                    secret,
+                    fields,
+                };
+
+                SectionSchema {
+                    fields: vec![
+                        field("dsn", true, vec![]),
+                        field("replica", false, vec![field("dsn", true, vec![])]),
+                    ],
+                    ..SectionSchema::new(Self::key())
+                }
+            }
+        }
+
+        let config = Config::builder()
+            .add_toml_str(
+                "[db]\nuser = \"admin\"\npassword = \"hunter2\"\ndsn = \"pg://admin:hunter2@db\"\n\
+                 [db.replica]\ndsn = \"pg://reader:s3cret@replica\"\n\
+                 [services.billing]\napi_token = \"abc\"\nport = 8080",
+            )
+            .build()
+            .expect("failed to build config");
+
+        let redaction = Redaction::new()
+            .key("*.password")
+            .key("**.*_token")
+            .secrets::<DbConfig>();
+
+        let exported = config
+            .to_toml_string(&redaction)
+            .expect("failed to serialize");
+
+        assert!(!exported.contains("hunter2"));
+        assert!(!exported.contains("s3cret"));
+        assert!(!exported.contains("abc"));
+
+        let table = toml::from_str::<Table>(&exported).expect("export must be valid TOML");
+        assert_eq!(table["db"]["user"].as_str(), Some("admin"));
+        assert_eq!(table["db"]["dsn"].as_str(), Some("***"));
+        assert_eq!(
+            table["services"]["billing"]["port"].as_integer(),
+            Some(8080)
+        );
+
+        let unredacted = config
+            .to_toml_string(&Redaction::default())
+            .expect("failed to serialize");
+        assert!(unredacted.contains("hunter2"));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_to_json_redacts_keys() {
+        let config = Config::builder()
+            .add_toml_str("[db]\npassword = \"hunter2\"\nport = 5432\ncreated = 1979-05-27")
+            .build()
+            .expect("failed to build config");
+
+        let json = config
+            .to_json(&crate::Redaction::new().key("**.password"))
+            .expect("failed to serialize");
+        let json = serde_json::from_str::<serde_json::Value>(&json).expect("valid JSON");
+
+        assert_eq!(json["db"]["password"], "***");
+        assert_eq!(json["db"]["port"], 5432);
+        assert_eq!(json["db"]["created"], "1979-05-27");
+    }
+
+    #[test]
+    fn test_unset_directive_from_environment() {
+        #[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+        struct CacheConfig {
+            ttl: u64,
+        }
+
+        impl ConfigItem for CacheConfig {
+            fn key() -> &'static str {
+                "cache"
+            }
+        }
+
+        unsafe { std::env::set_var("UNSETTEST__UNSET", "[\"cache\"]") };
+
+        let config = Config::builder()
+            .with_unset_key("unset")
+            .add_toml_str("[cache]\nttl = 60")
+            .add_env_prefix("UNSETTEST", "__")
+            .build()
+            .expect("failed to build config");
+
+        unsafe { std::env::remove_var("UNSETTEST__UNSET") };
+
+        assert_eq!(
+            config.get_or_default::<CacheConfig>(),
+            CacheConfig::default()
+        );
+        assert!(config.get_path::<Value>("unset").is_none());
+
+        let removed = &config.shadowed()[0];
+        assert_eq!(removed.key, "cache.ttl");
+        assert!(matches!(
+            removed.shadowed_by.source,
+            ConfigSourceInfo::Environment { .. }
+        ));
+    }
+
+    #[test]
+    fn test_include_directive() {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        let root = dir.path();
+
+        fs::create_dir(root.join("db")).expect("failed to create dir");
+        fs::write(
+            root.join("common.toml"),
+            "[telemetry]\nendpoint = \"otel:4317\"\nlevel = \"info\"",
+        )
+        .expect("failed to write");
+        fs::write(root.join("db/a.toml"), "[db]\nhost = \"a\"\nport = 5432")
+            .expect("failed to write");
+        fs::write(root.join("db/b.toml"), "[db]\nhost = \"b\"").expect("failed to write");
+        fs::write(
+            root.join("service.toml"),
+            "include = [\"common.toml\", \"db/*.toml\"]\n[telemetry]\nlevel = \"debug\"",
+        )
+        .expect("failed to write");
+
+        let config = Config::builder()
+            .with_include_key("include")
+            .add_required_file(root.join("service.toml"))
+            .build()
+            .expect("failed to build config");
+
+        assert_eq!(config.get_path::<String>("db.host").as_deref(), Some("b"));
+        assert_eq!(config.get_path::<u16>("db.port"), Some(5432));
+        assert_eq!(
+            config.get_path::<String>("telemetry.level").as_deref(),
+            Some("debug")
+        );
+        assert!(config.get_path::<Value>("include").is_none());
+
+        let files = config
+            .file_sources()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            files,
+            vec!["common.toml", "a.toml", "b.toml", "service.toml"]
+        );
+
+        assert!(matches!(
+            config.origin("telemetry.endpoint").map(|o| &o.source),
+            Some(ConfigSourceInfo::File { path, .. }) if path.ends_with("common.toml")
+        ));
+    }
+
+    #[test]
+    fn test_include_glob_skips_including_file() {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        let root = dir.path();
+
+        fs::create_dir(root.join("nested")).expect("failed to create dir");
+        fs::write(root.join("a.toml"), "[db]\nhost = \"a\"\nport = 5432").expect("failed to write");
+        fs::write(root.join("b.toml"), "[db]\nhost = \"b\"").expect("failed to write");
+        fs::write(root.join("nested/c.toml"), "[db]\nhost = \"c\"").expect("failed to write");
+        fs::write(
+            root.join("main.toml"),
+            "include = [\"*.toml\"]\n[db]\nname = \"main\"",
+        )
+        .expect("failed to write");
+
+        let config = Config::builder()
+            .with_include_key("include")
+            .add_required_file(root.join("main.toml"))
+            .build()
+            .expect("failed to build config");
+
+        assert_eq!(config.get_path::<String>("db.host").as_deref(), Some("b"));
+        assert_eq!(
+            config.get_path::<String>("db.name").as_deref(),
+            Some("main")
+        );
+
+        let files = config
+            .file_sources()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(files, vec!["a.toml", "b.toml", "main.toml"]);
+    }
+
+    #[test]
+    fn test_include_cycle_is_rejected() {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        let root = dir.path();
+
+        fs::write(root.join("a.toml"), "include = \"b.toml\"").expect("failed to write");
+        fs::write(root.join("b.toml"), "include = [\"a.toml\"]").expect("failed to write");
+
+        let error = Config::builder()
+            .with_include_key("include")
+            .add_required_file(root.join("a.toml"))
+            .build()
+            .unwrap_err();
+
+        assert!(matches!(error, ConfigError::IncludeCycle { ref paths } if paths.len() == 3));
+
+        fs::write(root.join("b.toml"), "include = [\"missing.toml\"]").expect("failed to write");
+
+        let error = Config::builder()
+            .with_include_key("include")
+            .add_required_file(root.join("a.toml"))
+            .build()
+            .unwrap_err();
+
+        assert!(matches!(error, ConfigError::FileNotFound(_)));
+    }
+
+    #[test]
+    fn test_include_key_is_plain_config_by_default() {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        let root = dir.path();
+
+        fs::write(root.join("other.toml"), "[db]\nhost = \"other\"").expect("failed to write");
+        fs::write(root.join("main.toml"), "include = \"other.toml\"").expect("failed to write");
+
+        let config = Config::builder()
+            .add_required_file(root.join("main.toml"))
+            .build()
+            .expect("failed to build config");
+
+        assert_eq!(
+            config.get_path::<String>("include").as_deref(),
+            Some("other.toml")
+        );
+        assert!(config.get_path::<String>("db.host").is_none());
+    }
+
+    #[test]
+    fn test_add_dir_loads_files_in_lexical_order() {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        let root = dir.path();
+
+        fs::write(
+            root.join("10-base.toml"),
+            "[server]\nhost = \"a\"\nport = 80",
+        )
+        .expect("failed to write");
+        fs::write(root.join("20-override.toml"), "[server]\nhost = \"b\"")
+            .expect("failed to write");
+        fs::write(root.join(".hidden.toml"), "[server]\nhost = \"hidden\"")
+            .expect("failed to write");
+        fs::write(root.join("README.md"), "not config").expect("failed to write");
+        fs::create_dir(root.join("..data")).expect("failed to create dir");
+
+        let config = Config::builder()
+            .add_required_dir(root)
+            .build()
+            .expect("failed to build config");
+
+        assert_eq!(
+            config.get_path::<String>("server.host").as_deref(),
+            Some("b")
+        );
+        assert_eq!(config.get_path::<u16>("server.port"), Some(80));
+        assert_eq!(config.file_sources().count(), 2);
+    }
+
+    #[test]
+    fn test_add_dir_skips_disabled_formats() {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        let root = dir.path();
+
+        fs::write(root.join("10-base.toml"), "[server]\nhost = \"toml\"").expect("failed to write");
+        fs::write(root.join("20-yaml.yaml"), "server:\n  host: yaml").expect("failed to write");
+        fs::write(root.join("30-json.json"), "{\"server\": {\"port\": 80}}")
+            .expect("failed to write");
+
+        let config = Config::builder()
+            .add_required_dir(root)
+            .build()
+            .expect("failed to build config");
+
+        let host = if cfg!(feature = "yaml") {
+            "yaml"
+        } else {
+            "toml"
+        };
+        let port = cfg!(feature = "json").then_some(80);
+
+        assert_eq!(
+            config.get_path::<String>("server.host").as_deref(),
+            Some(host)
+        );
+        assert_eq!(config.get_path::<u16>("server.port"), port);
+    }
+
+    #[test]
+    fn test_add_dir_missing_or_empty() {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        let missing = dir.path().join("missing");
+
+        assert!(
+            Config::builder()
+                .add_dir(&missing)
+                .add_dir(dir.path())
+                .build()
+                .is_ok()
+        );
+
+        assert!(matches!(
+            Config::builder().add_required_dir(&missing).build(),
+            Err(ConfigError::FileNotFound(_))
+        ));
+
+        assert!(matches!(
+            Config::builder().add_required_dir(dir.path()).build(),
+            Err(ConfigError::EmptyDirectory(_))
+        ));
+    }
+
+    #[test]
+    fn test_file_relative_to_exe() {
+        let exe_dir = std::env::current_exe()
+            .expect("current exe")
+            .parent()
+            .expect("exe dir")
+            .to_path_buf();
+
+        let name = format!("thisconfig-exe-relative-{}.toml", std::process::id());
+        let path = exe_dir.join(&name);
+        fs::write(&path, "[tool]\nname = \"cli\"").expect("failed to write");
+
+        let result = Config::builder()
+            .add_required_file_relative_to_exe(&name)
+            .build();
+        fs::remove_file(&path).expect("failed to remove");
+
+        let config = result.expect("failed to build config");
+        assert_eq!(
+            config.get_path::<String>("tool.name").as_deref(),
+            Some("cli")
+        );
+        assert_eq!(config.primary_file_source(), Some(path.as_path()));
+    }
+
+    #[test]
+    fn test_searched_file_candidates_and_required_flag() {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        let app = "thisconfig-search-test";
+
+        let candidates =
+            ConfigBuilder::search_candidates(app, Path::new("search.toml"), Some(dir.path()));
+        assert_eq!(candidates[0], Path::new("search.toml"));
+        assert_eq!(candidates[1], dir.path().join(app).join("search.toml"));
+        assert_eq!(
+            candidates[2],
+            Path::new("/etc").join(app).join("search.toml")
+        );
+
+        let path = dir.path().join("search.toml");
+        fs::write(&path, "[tool]\nname = \"found\"").expect("failed to write");
+
+        let config = Config::builder()
+            .add_searched_file(app, &path)
+            .build()
+            .expect("failed to build config");
+
+        assert_eq!(
+            config.get_path::<String>("tool.name").as_deref(),
+            Some("found")
+        );
+        assert!(matches!(
+            &config.sources()[0],
+            ConfigSourceInfo::File {
+                required: false,
+                found: true,
+                ..
+            }
+        ));
+
+        assert!(
+            Config::builder()
+                .add_searched_file(app, "missing.toml")
+                .build()
+                .is_ok()
+        );
+
+        assert!(matches!(
+            Config::builder()
+                .add_required_searched_file(app, "missing.toml")
+                .build(),
+            Err(ConfigError::FileNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_command_line_overrides() {
+        let config = Config::builder()
+            .add_args(["app", "--set", "database.port=5433", "--verbose"])
+            .add_args([
+                "--set=database.name=\"orders\"",
+                "--set=features=[\"a\", \"b\"]",
+            ])
+            .add_overrides([("database.host", "db.internal")])
+            .add_toml_str("[database]\nport = 5432\nhost = \"localhost\"\nname = \"app\"")
+            .build()
+            .expect("failed to build config");
+
+        assert_eq!(config.get_path::<u16>("database.port"), Some(5433));
+        assert_eq!(
+            config.get_path::<String>("database.host").as_deref(),
+            Some("db.internal")
+        );
+        assert_eq!(
+            config.get_path::<String>("database.name").as_deref(),
+            Some("orders")
+        );
+        assert_eq!(
+            config.get_path::<Vec<String>>("features"),
+            Some(vec!["a".to_string(), "b".to_string()])
+        );
+
+        let origin = config.origin("database.port").expect("missing origin");
+        assert_eq!(origin.source, ConfigSourceInfo::CommandLine);
+
+        let shadowed = config
+            .shadowed()
+            .iter()
+            .find(|shadowed| shadowed.key == "database.port")
+            .expect("missing shadowed value");
+        assert_eq!(shadowed.value, Value::Integer(5432));
+        assert_eq!(
+            config.sources().last(),
+            Some(&ConfigSourceInfo::CommandLine)
+        );
+    }
+
+    #[test]
+    fn test_malformed_command_line_override() {
+        for args in [
+            vec!["--set", "database.port"],
+            vec!["--set=.port=1"],
+            vec!["--set"],
+        ] {
+            assert!(matches!(
+                Config::builder().add_args(args).build(),
+                Err(ConfigError::InvalidOverride { .. })
+            ));
+        }
+    }
 }
@@ -1,3 +1,4 @@
+use crate::{FileFormat, KeyOrigin};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -5,6 +6,9 @@ pub enum ConfigError {
     #[error("Configuration file not found at {0}")]
     FileNotFound(String),
 
+    #[error("No configuration files found in directory {0}")]
+    EmptyDirectory(String),
+
     #[error("Failed to read configuration file: {source}")]
     ReadError {
         #[from]
@@ -14,6 +18,9 @@ pub enum ConfigError {
     #[error("Environment variable interpolation error: {message}")]
     InterpolationError { message: String },
 
+    #[error("Invalid configuration override '{assignment}', expected key.path=value")]
+    InvalidOverride { assignment: String },
+
     #[error("Configuration key '{key}' not found")]
     KeyNotFound { key: String },
 
@@ -23,9 +30,51 @@ pub enum ConfigError {
         source: toml::de::Error,
     },
 
+    #[error("Failed to parse {format} configuration: {message}")]
+    ParseError {
+        format: &'static str,
+        message: String,
+    },
+
+    #[error("Failed to serialize configuration as {format}: {message}")]
+    SerializeError {
+        format: &'static str,
+        message: String,
+    },
+
+    #[error(
+        "Invalid configuration for '{key}'{}: {message}{}",
+        field.as_ref().map(|f| format!(" at '{f}'")).unwrap_or_default(),
+        origin.as_ref().map(|o| format!(" (from {o})")).unwrap_or_default()
+    )]
+    SectionError {
+        /// Section key passed to `ConfigItem::key`.
+        key: String,
+        /// Path of the offending field inside the section, e.g. `pool.size`.
+        field: Option<String>,
+        /// Type the field was expected to have, when reported by serde.
+        expected: Option<String>,
+        message: String,
+        /// Source that supplied the offending value.
+        origin: Option<Box<KeyOrigin>>,
+    },
+
     #[error("Validation error: {message}")]
     ValidationError { message: String },
 
+    #[error(
+        "{} required configuration section(s) failed to load:{}",
+        errors.len(),
+        errors.iter().map(|e| format!("\n  - {e}")).collect::<String>()
+    )]
+    InvalidSections { errors: Vec<ConfigError> },
+
+    #[error(
+        "Include cycle detected: {}",
+        paths.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(" -> ")
+    )]
+    IncludeCycle { paths: Vec<std::path::PathBuf> },
+
     #[error("No configuration sources configured")]
     NoSourcesConfigured,
 
@@ -38,7 +87,46 @@ impl ConfigError {
         Self::InterpolationError { message }
     }
 
+    pub fn parse_error(format: FileFormat, message: impl ToString) -> Self {
+        Self::ParseError {
+            format: format.name(),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn serialize_error(format: FileFormat, message: impl ToString) -> Self {
+        Self::SerializeError {
+            format: format.name(),
+            message: message.to_string(),
+        }
+    }
+
     pub fn key_not_found(key: impl Into<String>) -> Self {
         Self::KeyNotFound { key: key.into() }
     }
+
+    /// Describes the error without configuration values, naming only section keys,
+    /// field paths and files, so it can be shown outside the process.
+    pub fn summary(&self) -> String {
+        match self {
+            Self::InterpolationError { .. } => "Environment variable interpolation error".into(),
+            Self::InvalidOverride { .. } => "Invalid configuration override".into(),
+            Self::DeserializeError { .. } => "Deserialization error".into(),
+            Self::ParseError { format, .. } => format!("Failed to parse {format} configuration"),
+            Self::SectionError { key, field, .. } => match field {
+                Some(field) => format!("Invalid configuration for '{key}' at '{field}'"),
+                None => format!("Invalid configuration for '{key}'"),
+            },
+            Self::ValidationError { .. } => "Validation error".into(),
+            Self::InvalidSections { errors } => format!(
+                "{} required configuration section(s) failed to load:{}",
+                errors.len(),
+                errors
+                    .iter()
+                    .map(|e| format!("\n  - {}", e.summary()))
+                    .collect::<String>()
+            ),
+            _ => self.to_string(),
+        }
+    }
 }
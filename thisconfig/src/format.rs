@@ -0,0 +1,291 @@
+use crate::ConfigError;
+use std::path::Path;
+use toml::Table;
+
+/// File formats understood by file sources, detected from the file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    Toml,
+    /// Requires the `yaml` feature.
+    Yaml,
+    /// Requires the `json` feature.
+    Json,
+}
+
+impl FileFormat {
+    /// Detects the format from the extension, defaulting to TOML.
+    pub fn from_path(path: &Path) -> Self {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase);
+
+        match extension.as_deref() {
+            Some("yaml" | "yml") => Self::Yaml,
+            Some("json") => Self::Json,
+            _ => Self::Toml,
+        }
+    }
+
+    /// Whether the feature needed to parse this format is enabled.
+    pub const fn is_enabled(self) -> bool {
+        match self {
+            Self::Toml => true,
+            Self::Yaml => cfg!(feature = "yaml"),
+            Self::Json => cfg!(feature = "json"),
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Toml => "TOML",
+            Self::Yaml => "YAML",
+            Self::Json => "JSON",
+        }
+    }
+
+    /// Parses `content` into the TOML table model shared by every source.
+    pub(crate) fn parse(self, content: &str) -> Result<Table, ConfigError> {
+        match self {
+            Self::Toml => Ok(toml::from_str::<Table>(content)?),
+            Self::Yaml => Self::parse_yaml(content),
+            Self::Json => Self::parse_json(content),
+        }
+    }
+
+    #[cfg(feature = "yaml")]
+    fn parse_yaml(content: &str) -> Result<Table, ConfigError> {
+        let value = serde_norway::from_str::<serde_norway::Value>(content)
+            .map_err(|e| ConfigError::parse_error(Self::Yaml, e))?;
+
+        match yaml::convert(value).map_err(|e| ConfigError::parse_error(Self::Yaml, e))? {
+            Some(toml::Value::Table(table)) => Ok(table),
+            None => Ok(Table::new()),
+            Some(_) => Err(ConfigError::parse_error(
+                Self::Yaml,
+                "top-level value must be a mapping",
+            )),
+        }
+    }
+
+    #[cfg(not(feature = "yaml"))]
+    fn parse_yaml(_: &str) -> Result<Table, ConfigError> {
+        Err(ConfigError::parse_error(
+            Self::Yaml,
+            "YAML support requires the `yaml` feature",
+        ))
+    }
+
+    #[cfg(feature = "json")]
+    fn parse_json(content: &str) -> Result<Table, ConfigError> {
+        let value = serde_json::from_str::<serde_json::Value>(content)
+            .map_err(|e| ConfigError::parse_error(Self::Json, e))?;
+
+        match json::convert(value).map_err(|e| ConfigError::parse_error(Self::Json, e))? {
+            Some(toml::Value::Table(table)) => Ok(table),
+            _ => Err(ConfigError::parse_error(
+                Self::Json,
+                "top-level value must be an object",
+            )),
+        }
+    }
+
+    #[cfg(not(feature = "json"))]
+    fn parse_json(_: &str) -> Result<Table, ConfigError> {
+        Err(ConfigError::parse_error(
+            Self::Json,
+            "JSON support requires the `json` feature",
+        ))
+    }
+}
+
+/// Null values have no TOML representation: they are dropped from tables and rejected
+/// inside arrays, where dropping them would shift the indices of later items.
+#[cfg(feature = "json")]
+pub(crate) mod json {
+    use serde_json::Value as Json;
+    use toml::{Table, Value};
+
+    pub(super) fn convert(value: Json) -> Result<Option<Value>, String> {
+        let converted = match value {
+            Json::Null => return Ok(None),
+            Json::Bool(b) => Value::Boolean(b),
+            Json::Number(n) => match (n.as_i64(), n.as_u64()) {
+                (Some(i), _) => Value::Integer(i),
+                (None, Some(_)) => return Err(format!("integer {n} is out of range for TOML")),
+                (None, None) => {
+                    Value::Float(n.as_f64().ok_or_else(|| format!("invalid number {n}"))?)
+                }
+            },
+            Json::String(s) => Value::String(s),
+            Json::Array(items) => {
+                let mut array = Vec::with_capacity(items.len());
+
+                for item in items {
+                    let item = convert(item)?.ok_or("null is not allowed inside arrays")?;
+                    array.push(item);
+                }
+
+                Value::Array(array)
+            }
+            Json::Object(map) => {
+                let mut table = Table::new();
+
+                for (key, item) in map {
+                    if let Some(item) = convert(item)? {
+                        table.insert(key, item);
+                    }
+                }
+
+                Value::Table(table)
+            }
+        };
+
+        Ok(Some(converted))
+    }
+
+    /// Converts back to JSON, writing datetimes as strings.
+    pub(crate) fn from_toml(value: &Value) -> Json {
+        match value {
+            Value::String(s) => Json::String(s.clone()),
+            Value::Integer(i) => Json::from(*i),
+            Value::Float(f) => Json::from(*f),
+            Value::Boolean(b) => Json::Bool(*b),
+            Value::Datetime(datetime) => Json::String(datetime.to_string()),
+            Value::Array(items) => Json::Array(items.iter().map(from_toml).collect()),
+            Value::Table(table) => Json::Object(
+                table
+                    .iter()
+                    .map(|(key, value)| (key.clone(), from_toml(value)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Null values have no TOML representation: they are dropped from mappings and rejected
+/// inside sequences, where dropping them would shift the indices of later items.
+#[cfg(feature = "yaml")]
+mod yaml {
+    use serde_norway::Value as Yaml;
+    use toml::{Table, Value};
+
+    pub(super) fn convert(value: Yaml) -> Result<Option<Value>, String> {
+        let converted = match value {
+            Yaml::Null => return Ok(None),
+            Yaml::Bool(b) => Value::Boolean(b),
+            Yaml::Number(n) => match (n.as_i64(), n.as_u64()) {
+                (Some(i), _) => Value::Integer(i),
+                (None, Some(_)) => return Err(format!("integer {n} is out of range for TOML")),
+                (None, None) => {
+                    Value::Float(n.as_f64().ok_or_else(|| format!("invalid number {n}"))?)
+                }
+            },
+            Yaml::String(s) => Value::String(s),
+            Yaml::Sequence(items) => {
+                let mut array = Vec::with_capacity(items.len());
+
+                for item in items {
+                    let item = convert(item)?.ok_or("null is not allowed inside arrays")?;
+                    array.push(item);
+                }
+
+                Value::Array(array)
+            }
+            Yaml::Mapping(map) => {
+                let mut table = Table::new();
+
+                for (key, item) in map {
+                    let key = match key {
+                        Yaml::String(s) => s,
+                        Yaml::Bool(b) => b.to_string(),
+                        Yaml::Number(n) => n.to_string(),
+                        other => return Err(format!("unsupported mapping key {other:?}")),
+                    };
+
+                    if let Some(item) = convert(item)? {
+                        table.insert(key, item);
+                    }
+                }
+
+                Value::Table(table)
+            }
+            Yaml::Tagged(tagged) => return convert(tagged.value),
+        };
+
+        Ok(Some(converted))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_path_detects_extension() {
+        assert_eq!(FileFormat::from_path(Path::new("a.toml")), FileFormat::Toml);
+        assert_eq!(FileFormat::from_path(Path::new("a.YML")), FileFormat::Yaml);
+        assert_eq!(FileFormat::from_path(Path::new("a.yaml")), FileFormat::Yaml);
+        assert_eq!(FileFormat::from_path(Path::new("a.json")), FileFormat::Json);
+        assert_eq!(FileFormat::from_path(Path::new("config")), FileFormat::Toml);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_parse_json_into_table() {
+        let table = FileFormat::Json
+            .parse(r#"{"server": {"port": 8080, "ratio": 0.5, "tags": ["a"], "opt": null}}"#)
+            .expect("failed to parse");
+
+        let server = table["server"].as_table().expect("server table");
+        assert_eq!(server["port"].as_integer(), Some(8080));
+        assert_eq!(server["ratio"].as_float(), Some(0.5));
+        assert_eq!(server["tags"].as_array().map(Vec::len), Some(1));
+        assert!(!server.contains_key("opt"));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_parse_json_rejects_lossy_values() {
+        assert!(
+            FileFormat::Json
+                .parse(r#"{"id": 18446744073709551615}"#)
+                .is_err()
+        );
+        assert!(
+            FileFormat::Json
+                .parse(r#"{"tags": ["a", null, "b"]}"#)
+                .is_err()
+        );
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_parse_yaml_into_table() {
+        let table = FileFormat::Yaml
+            .parse("server:\n  host: localhost\n  port: 8080\n  debug: true\n")
+            .expect("failed to parse");
+
+        let server = table["server"].as_table().expect("server table");
+        assert_eq!(server["host"].as_str(), Some("localhost"));
+        assert_eq!(server["port"].as_integer(), Some(8080));
+        assert_eq!(server["debug"].as_bool(), Some(true));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_parse_yaml_rejects_non_mapping_root() {
+        assert!(FileFormat::Yaml.parse("- a\n- b\n").is_err());
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_parse_yaml_rejects_lossy_values() {
+        assert!(
+            FileFormat::Yaml
+                .parse("id: 18446744073709551615\n")
+                .is_err()
+        );
+        assert!(FileFormat::Yaml.parse("tags: [a, ~, b]\n").is_err());
+    }
+}
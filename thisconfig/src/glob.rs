@@ -0,0 +1,83 @@
+/// Matches `text` against a glob `pattern`, both split into segments on `separator`.
+///
+/// `*` and `?` match within a single segment, while a `**` segment matches any
+/// number of segments, including none.
+pub(crate) fn matches(pattern: &str, text: &str, separator: char) -> bool {
+    let pattern = pattern.split(separator).collect::<Vec<_>>();
+    let text = text.split(separator).collect::<Vec<_>>();
+
+    matches_segments(&pattern, &text)
+}
+
+/// Returns whether `pattern` contains glob metacharacters.
+pub(crate) fn is_pattern(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
+
+fn matches_segments(pattern: &[&str], text: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((&"**", rest)) => (0..=text.len()).any(|skip| matches_segments(rest, &text[skip..])),
+        Some((first, rest)) => text.split_first().is_some_and(|(segment, text)| {
+            matches_segment(first, segment) && matches_segments(rest, text)
+        }),
+    }
+}
+
+fn matches_segment(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text position it is currently matched up to.
+    let mut backtrack = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                p += 1;
+                backtrack = Some((p, t));
+            }
+            Some('?') => {
+                p += 1;
+                t += 1;
+            }
+            Some(&c) if c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_segment_wildcards() {
+        assert!(matches("*.password", "db.password", '.'));
+        assert!(matches("db.*_key", "db.api_key", '.'));
+        assert!(matches("db.?ort", "db.port", '.'));
+        assert!(!matches("*.password", "password", '.'));
+        assert!(!matches("*.password", "a.db.password", '.'));
+    }
+
+    #[test]
+    fn test_double_star_matches_any_depth() {
+        assert!(matches("**.password", "password", '.'));
+        assert!(matches("**.password", "a.db.password", '.'));
+        assert!(matches("services/**/*.toml", "services/a/b/c.toml", '/'));
+        assert!(!matches("services/**/*.toml", "other/c.toml", '/'));
+    }
+}
@@ -1,92 +1,611 @@
 use regex_lite::Regex;
-use std::env;
-use toml::Value;
+use std::{
+    collections::HashMap,
+    env, fmt, fs, io,
+    sync::{Arc, LazyLock},
+};
+use toml::{Table, Value};
+
+/// When placeholders are expanded relative to parsing a source.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InterpolationMode {
+    /// Expands placeholders in the raw text before it is parsed.
+    ///
+    /// Placeholders work anywhere, including unquoted values such as `port = ${PORT}`,
+    /// but expanded values become part of the document: a quote, newline or bracket
+    /// in an environment variable can break the file or inject keys.
+    #[default]
+    Raw,
+    /// Parses the source first and expands placeholders inside string values only.
+    ///
+    /// Expanded values can never change the structure of the document and `file:`
+    /// references must be written as the whole string. Strings are coerced to numbers or
+    /// booleans when the target field requires it, so `port = "${PORT}"` still
+    /// deserializes into a `u16`.
+    Values,
+}
+
+/// Prefix of `${config:path}` references between keys.
+const REFERENCE: &str = "config:";
+
+/// A `file:path` reference in raw text, with an optional `:default`.
+static FILE_REFERENCE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"file:([^:\s"'`\]\)]+)(?::([^:\s"'`\]\)]+))?"#)
+        .expect("Failed to compile FILE_REFERENCE")
+});
+
+/// Resolves the argument of `${scheme:argument}` placeholders for a registered scheme.
+///
+/// Returning `Ok(None)` means the value does not exist, which lets `${VAR:-default}`
+/// fall back for the `env` scheme; `Err` aborts loading with the given message.
+/// Closures `Fn(&str) -> Result<Option<String>, String>` implement this trait.
+pub trait Resolver: Send + Sync + 'static {
+    fn resolve(&self, argument: &str) -> Result<Option<String>, String>;
+}
+
+impl<F> Resolver for F
+where
+    F: Fn(&str) -> Result<Option<String>, String> + Send + Sync + 'static,
+{
+    fn resolve(&self, argument: &str) -> Result<Option<String>, String> {
+        self(argument)
+    }
+}
+
+/// Reads environment variables; backs `${VAR}` and `${env:VAR}`.
+struct EnvResolver;
+
+impl Resolver for EnvResolver {
+    fn resolve(&self, name: &str) -> Result<Option<String>, String> {
+        Ok(env::var(name).ok())
+    }
+}
+
+/// Reads whole files; backs `file:path` and `${file:path}`.
+struct FileResolver;
+
+impl Resolver for FileResolver {
+    fn resolve(&self, path: &str) -> Result<Option<String>, String> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(Some(content)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("Failed to read file '{path}': {e}")),
+        }
+    }
+}
+
+/// Expands placeholders using the resolvers registered for each scheme.
+///
+/// `env` and `file` are registered by default and can be replaced.
+#[derive(Clone)]
+pub struct Interpolator {
+    resolvers: HashMap<String, Arc<dyn Resolver>>,
+}
+
+impl Default for Interpolator {
+    fn default() -> Self {
+        let mut interpolator = Self {
+            resolvers: HashMap::new(),
+        };
+
+        interpolator.register("env", EnvResolver);
+        interpolator.register("file", FileResolver);
+        interpolator
+    }
+}
 
-pub struct Interpolator;
+impl fmt::Debug for Interpolator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut schemes = self.resolvers.keys().collect::<Vec<_>>();
+        schemes.sort();
+
+        f.debug_struct("Interpolator")
+            .field("schemes", &schemes)
+            .finish()
+    }
+}
 
 impl Interpolator {
-    pub fn interpolate(content: &str) -> Result<String, String> {
-        let env_expanded = Self::interpolate_env_variables(content)?;
-        Self::interpolate_files(&env_expanded)
+    pub fn register<R: Resolver>(&mut self, scheme: &str, resolver: R) {
+        self.resolvers
+            .insert(scheme.to_string(), Arc::new(resolver));
+    }
+
+    pub fn interpolate(&self, content: &str) -> Result<String, String> {
+        let expanded = self.expand(content, true)?;
+        self.interpolate_files(&expanded)
     }
 
-    fn interpolate_env_variables(content: &str) -> Result<String, String> {
-        let mut result = content.to_string();
+    /// Expands every string value of an already parsed table.
+    pub fn interpolate_table(&self, table: &mut Table) -> Result<(), String> {
+        table.iter_mut().try_for_each(|(key, value)| {
+            self.interpolate_value(value)
+                .map_err(|e| format!("{e} (in '{key}')"))
+        })
+    }
 
-        // Matches ${VAR:default}
-        let env_var_with_fallback_re = Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*):([^}]*)\}")
-            .expect("Failed to compile ENV_VAR_WITH_FALLBACK_RE");
+    fn interpolate_value(&self, value: &mut Value) -> Result<(), String> {
+        match value {
+            Value::String(s) => *s = self.interpolate_string(s)?,
+            Value::Array(items) => items
+                .iter_mut()
+                .try_for_each(|item| self.interpolate_value(item))?,
+            Value::Table(table) => self.interpolate_table(table)?,
+            _ => {}
+        }
 
-        for caps in env_var_with_fallback_re.captures_iter(content) {
-            let default_value = &caps[2];
-            let replacement = env::var(&caps[1]).unwrap_or_else(|_| default_value.to_string());
+        Ok(())
+    }
 
-            result = result.replace(&caps[0], &replacement);
+    /// Expands a single string value. A `file:path` or `file:path:default` reference
+    /// must be written as the whole string; expanded values are never taken as one, so
+    /// an environment variable cannot make the config read an arbitrary file.
+    fn interpolate_string(&self, s: &str) -> Result<String, String> {
+        let Some(reference) = s.strip_prefix("file:") else {
+            return self.expand(s, false);
+        };
+
+        let reference = self.expand(reference, false)?;
+
+        match reference.split_once(':') {
+            Some((path, default)) => Ok(self
+                .resolve("file", path)
+                .ok()
+                .flatten()
+                .unwrap_or_else(|| default.to_string())),
+            None => self
+                .resolve("file", &reference)?
+                .ok_or_else(|| format!("Failed to read file '{reference}': not found")),
         }
+    }
 
-        let content = result.clone();
+    fn resolve(&self, scheme: &str, argument: &str) -> Result<Option<String>, String> {
+        match self.resolvers.get(scheme) {
+            Some(resolver) => resolver.resolve(argument),
+            None => Err(format!("no resolver registered for scheme '{scheme}'")),
+        }
+    }
 
-        // Matches ${VAR} — sin fallback, falla si no existe
-        let env_var_braced_re = Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}")
-            .expect("Failed to compile ENV_VAR_BRACED_RE");
+    /// Expands `${...}` expressions with shell-style semantics:
+    ///
+    /// - `${VAR}` fails if `VAR` is not set.
+    /// - `${VAR:-default}` uses `default` if `VAR` is unset or empty.
+    /// - `${VAR:default}` uses `default` only if `VAR` is unset, keeping an empty value.
+    /// - `${VAR:?message}` fails with `message` if `VAR` is unset or empty.
+    /// - `${VAR:+alt}` uses `alt` if `VAR` is set and not empty, and an empty string otherwise.
+    /// - `${scheme:argument}` calls the resolver registered for `scheme`.
+    /// - `${config:path}` is kept as is and resolved after merging, see `resolve_references`.
+    /// - `$${` produces a literal `${`.
+    ///
+    /// Words may contain nested expressions and balanced braces; they are only
+    /// expanded when used, so `${A:-${B}}` does not require `B` when `A` is set.
+    ///
+    /// With `raw`, the expansion becomes document text and the output of resolvers
+    /// other than `env` is escaped like `file:` references, so a secret holding quotes
+    /// cannot break the string.
+    fn expand(&self, content: &str, raw: bool) -> Result<String, String> {
+        let mut result = String::with_capacity(content.len());
+        let mut rest = content;
+
+        while let Some(index) = rest.find('$') {
+            result.push_str(&rest[..index]);
+            rest = &rest[index..];
+
+            if let Some(after) = rest.strip_prefix("$${") {
+                // Escaped references are unescaped by `resolve_references`.
+                if after.starts_with(REFERENCE) {
+                    result.push('$');
+                }
+
+                result.push_str("${");
+                rest = after;
+                continue;
+            }
+
+            if let Some(after) = rest.strip_prefix("${")
+                && let Some((expression, after)) = Expression::parse(after, self)?
+            {
+                result.push_str(&expression.evaluate(self, raw)?);
+                rest = after;
+                continue;
+            }
+
+            result.push('$');
+            rest = &rest[1..];
+        }
 
-        for caps in env_var_braced_re.captures_iter(&content) {
-            let var_name = &caps[1];
+        result.push_str(rest);
 
-            let val = env::var(var_name)
-                .map_err(|_| format!("environment variable '{var_name}' not found"))?;
+        Ok(result)
+    }
 
-            result = result.replace(&caps[0], &val);
+    /// Replaces `file:path` and `file:path:default` references in raw text with the
+    /// escaped file content, in a single pass over `content`.
+    fn interpolate_files(&self, content: &str) -> Result<String, String> {
+        let mut result = String::with_capacity(content.len());
+        let mut last = 0;
+
+        for caps in FILE_REFERENCE.captures_iter(content) {
+            let Some(reference) = caps.get(0) else {
+                continue;
+            };
+
+            let path = &caps[1];
+
+            let replacement = match caps.get(2) {
+                Some(default) => self
+                    .resolve("file", path)
+                    .ok()
+                    .flatten()
+                    .map(|c| Self::escape_toml_string(&c))
+                    .unwrap_or_else(|| default.as_str().to_string()),
+                None => self
+                    .resolve("file", path)?
+                    .map(|c| Self::escape_toml_string(&c))
+                    .ok_or_else(|| format!("Failed to read file '{path}': not found"))?,
+            };
+
+            result.push_str(&content[last..reference.start()]);
+            result.push_str(&replacement);
+            last = reference.end();
         }
 
+        result.push_str(&content[last..]);
+
         Ok(result)
     }
 
-    fn interpolate_files(content: &str) -> Result<String, String> {
-        let mut result = content.to_string();
+    /// Escapes `s` for the inside of a basic (double-quoted) TOML string.
+    fn escape_toml_string(s: &str) -> String {
+        let mut escaped = String::with_capacity(s.len());
+
+        for c in s.chars() {
+            match c {
+                '"' => escaped.push_str("\\\""),
+                '\\' => escaped.push_str("\\\\"),
+                '\n' => escaped.push_str("\\n"),
+                '\r' => escaped.push_str("\\r"),
+                '\t' => escaped.push_str("\\t"),
+                c if c.is_control() => escaped.push_str(&format!("\\u{:04X}", c as u32)),
+                c => escaped.push(c),
+            }
+        }
+
+        escaped
+    }
+}
+
+/// Replaces `${config:path}` references in the string values of the merged table.
+///
+/// A string made of a single reference takes the referenced value with its type,
+/// so `port = "${config:server.port}"` stays an integer; otherwise the referenced
+/// value must be a scalar and is inserted as text. `$${config:` is kept literally.
+///
+/// Keys for which `literal` returns `true` were never interpolated, such as
+/// environment overlays, and are left untouched.
+pub(crate) fn resolve_references(
+    table: &mut Table,
+    literal: &dyn Fn(&str) -> bool,
+) -> Result<(), String> {
+    let source = table.clone();
+    let mut references = References {
+        source: &source,
+        literal,
+        resolved: HashMap::new(),
+        stack: Vec::new(),
+    };
+
+    references.resolve_table(table, "")
+}
+
+struct References<'a> {
+    source: &'a Table,
+    /// Whether the value at a dotted path comes from a source that is not interpolated.
+    literal: &'a dyn Fn(&str) -> bool,
+    /// Values of the keys resolved so far, by dotted path.
+    resolved: HashMap<String, Value>,
+    /// Keys being resolved, used to detect cycles.
+    stack: Vec<String>,
+}
 
-        // Matches file:/path/to/file:default_value
-        let file_with_fallback_re = Regex::new(r#"file:([^:"'\s]+):([^:\s"'`\]\)]+)"#)
-            .expect("Failed to compile FILE_WITH_FALLBACK_RE");
+impl References<'_> {
+    fn resolve_table(&mut self, table: &mut Table, prefix: &str) -> Result<(), String> {
+        for (key, value) in table.iter_mut() {
+            let path = match prefix {
+                "" => key.clone(),
+                _ => format!("{prefix}.{key}"),
+            };
 
-        for caps in file_with_fallback_re.captures_iter(content) {
-            let default_value = caps[2].to_string();
+            self.resolve_value(value, &path)?;
+        }
 
-            let replacement = std::fs::read_to_string(&caps[1])
-                .map(|c| Self::escape_toml_string(&c))
-                .unwrap_or(default_value);
+        Ok(())
+    }
 
-            result = result.replace(&caps[0], &replacement);
+    fn resolve_value(&mut self, value: &mut Value, path: &str) -> Result<(), String> {
+        match value {
+            _ if (self.literal)(path) => {}
+            Value::String(s) if s.contains("${") => *value = self.substitute(s, path)?,
+            Value::Array(items) => {
+                for (i, item) in items.iter_mut().enumerate() {
+                    self.resolve_value(item, &format!("{path}[{i}]"))?;
+                }
+            }
+            Value::Table(table) => self.resolve_table(table, path)?,
+            _ => {}
         }
 
-        let content = result.clone();
+        Ok(())
+    }
+
+    /// Returns the fully resolved value stored at `path`.
+    fn lookup(&mut self, path: &str) -> Result<Value, String> {
+        if let Some(value) = self.resolved.get(path) {
+            return Ok(value.clone());
+        }
 
-        // Matches file:/path/to/file
-        let file_simple_re =
-            Regex::new(r#"file:([^:\s"'`\]\)]+)"#).expect("Failed to compile FILE_SIMPLE_RE");
+        if let Some(start) = self.stack.iter().position(|p| p == path) {
+            let mut cycle = self.stack[start..].to_vec();
+            cycle.push(path.to_string());
+
+            return Err(format!("cyclic config reference: {}", cycle.join(" -> ")));
+        }
 
-        for caps in file_simple_re.captures_iter(&content) {
-            let file_path = caps[1].to_string();
+        let mut value = crate::config::lookup(self.source, path)
+            .cloned()
+            .ok_or_else(|| format!("config reference '{path}' not found"))?;
 
-            let replacement = std::fs::read_to_string(&file_path)
-                .map(|c| Self::escape_toml_string(&c))
-                .map_err(|e| format!("Failed to read file '{file_path}': {e}"))?;
+        self.stack.push(path.to_string());
+        let result = self.resolve_value(&mut value, path);
+        self.stack.pop();
+        result?;
 
-            result = result.replace(&caps[0], &replacement);
+        self.resolved.insert(path.to_string(), value.clone());
+
+        Ok(value)
+    }
+
+    /// Expands the references of the string stored at `path`.
+    fn substitute(&mut self, s: &str, path: &str) -> Result<Value, String> {
+        // `path` is already on the stack when it is resolved through `lookup`.
+        let pushed = self.stack.last().is_none_or(|last| last != path);
+
+        if pushed {
+            self.stack.push(path.to_string());
         }
 
-        Ok(result)
+        let result = self.expand(s, path);
+
+        if pushed {
+            self.stack.pop();
+        }
+
+        result
     }
 
-    fn escape_toml_string(s: &str) -> String {
-        let val = Value::String(s.to_string());
-        let toml_str = val.to_string();
+    fn expand(&mut self, s: &str, path: &str) -> Result<Value, String> {
+        let reference = format!("${{{REFERENCE}");
 
-        let unquoted = toml_str.strip_prefix('"').and_then(|s| s.strip_suffix('"'));
+        if let Some(target) = s
+            .strip_prefix(&reference)
+            .and_then(|rest| rest.strip_suffix('}'))
+            .filter(|target| !target.contains(['{', '}']))
+        {
+            return self.lookup(target.trim());
+        }
 
-        match unquoted {
-            Some(inner) => inner.to_string(),
-            None => toml_str,
+        let mut result = String::with_capacity(s.len());
+        let mut rest = s;
+
+        while let Some(index) = rest.find('$') {
+            result.push_str(&rest[..index]);
+            rest = &rest[index..];
+
+            if let Some(after) = rest.strip_prefix("$${") {
+                result.push_str("${");
+                rest = after;
+                continue;
+            }
+
+            if let Some(after) = rest.strip_prefix(&reference) {
+                let (target, after) = Expression::word(REFERENCE, after)?;
+
+                match self.lookup(target.trim())? {
+                    Value::String(text) => result.push_str(&text),
+                    Value::Array(_) | Value::Table(_) => {
+                        return Err(format!(
+                            "config reference '{target}' in '{path}' is not a scalar value"
+                        ));
+                    }
+                    other => result.push_str(&other.to_string()),
+                }
+
+                rest = after;
+                continue;
+            }
+
+            result.push('$');
+            rest = &rest[1..];
+        }
+
+        result.push_str(rest);
+
+        Ok(Value::String(result))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    /// `:-`, applied when the variable is unset or empty.
+    Default,
+    /// `:`, applied only when the variable is unset.
+    Unset,
+    /// `:?`
+    Required,
+    /// `:+`
+    Alternate,
+}
+
+/// A single `${...}` expression, with its word or argument still unexpanded.
+#[derive(Debug)]
+enum Expression<'a> {
+    /// `${NAME}` or `${NAME<op>word}`, resolved through the `env` scheme.
+    Variable {
+        name: &'a str,
+        operator: Option<(Operator, &'a str)>,
+    },
+    /// `${scheme:argument}` for a registered scheme.
+    Resolver { scheme: &'a str, argument: &'a str },
+    /// `${config:path}`, left in place until every source is merged.
+    Reference { path: &'a str },
+}
+
+impl<'a> Expression<'a> {
+    /// Parses the text following `${`, returning the expression and the remaining input.
+    ///
+    /// Returns `None` when the text does not start with a variable name or a
+    /// registered scheme, so the `${` is kept literally. Registered schemes take
+    /// precedence over the `${VAR:default}` shorthand.
+    fn parse(
+        input: &'a str,
+        interpolator: &Interpolator,
+    ) -> Result<Option<(Self, &'a str)>, String> {
+        let length = input
+            .char_indices()
+            .find(|&(i, c)| {
+                !(c == '_'
+                    || c.is_ascii_alphabetic()
+                    || (i > 0 && (c.is_ascii_digit() || c == '-')))
+            })
+            .map_or(input.len(), |(i, _)| i);
+
+        if length == 0 {
+            return Ok(None);
+        }
+
+        let (name, rest) = input.split_at(length);
+
+        if let Some(rest) = input.strip_prefix(REFERENCE) {
+            let (path, rest) = Self::word(name, rest)?;
+            return Ok(Some((Self::Reference { path }, rest)));
+        }
+
+        if interpolator.resolvers.contains_key(name)
+            && let Some(rest) = rest.strip_prefix(':')
+        {
+            let (argument, rest) = Self::word(name, rest)?;
+            return Ok(Some((
+                Self::Resolver {
+                    scheme: name,
+                    argument,
+                },
+                rest,
+            )));
+        }
+
+        if name.contains('-') {
+            return Ok(None);
+        }
+
+        if let Some(rest) = rest.strip_prefix('}') {
+            return Ok(Some((
+                Self::Variable {
+                    name,
+                    operator: None,
+                },
+                rest,
+            )));
+        }
+
+        let (operator, rest) = if let Some(rest) = rest.strip_prefix(":-") {
+            (Operator::Default, rest)
+        } else if let Some(rest) = rest.strip_prefix(":?") {
+            (Operator::Required, rest)
+        } else if let Some(rest) = rest.strip_prefix(":+") {
+            (Operator::Alternate, rest)
+        } else if let Some(rest) = rest.strip_prefix(':') {
+            (Operator::Unset, rest)
+        } else {
+            return Ok(None);
+        };
+
+        let (word, rest) = Self::word(name, rest)?;
+
+        Ok(Some((
+            Self::Variable {
+                name,
+                operator: Some((operator, word)),
+            },
+            rest,
+        )))
+    }
+
+    /// Splits the text before the `}` closing the expression, skipping balanced braces.
+    fn word(name: &str, input: &'a str) -> Result<(&'a str, &'a str), String> {
+        let mut depth = 0usize;
+
+        for (i, c) in input.char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' if depth == 0 => return Ok((&input[..i], &input[i + 1..])),
+                '}' => depth -= 1,
+                _ => {}
+            }
+        }
+
+        Err(format!("unterminated expression '${{{name}'"))
+    }
+
+    fn evaluate(&self, interpolator: &Interpolator, raw: bool) -> Result<String, String> {
+        let (name, operator) = match *self {
+            Self::Variable { name, operator } => (name, operator),
+            Self::Resolver { scheme, argument } => {
+                let argument = interpolator.expand(argument, false)?;
+
+                return interpolator
+                    .resolve(scheme, &argument)?
+                    .map(|value| {
+                        // `${env:VAR}` stays equivalent to `${VAR}`, which is inserted as is.
+                        if raw && scheme != "env" {
+                            Interpolator::escape_toml_string(&value)
+                        } else {
+                            value
+                        }
+                    })
+                    .ok_or_else(|| format!("{scheme} resolver found no value for '{argument}'"));
+            }
+            Self::Reference { path } => return Ok(format!("${{{REFERENCE}{path}}}")),
+        };
+
+        let value = interpolator.resolve("env", name)?;
+        let non_empty = value.clone().filter(|value| !value.is_empty());
+
+        match operator {
+            None => value.ok_or_else(|| format!("environment variable '{name}' not found")),
+            Some((Operator::Default, word)) => match non_empty {
+                Some(value) => Ok(value),
+                None => interpolator.expand(word, raw),
+            },
+            Some((Operator::Unset, word)) => match value {
+                Some(value) => Ok(value),
+                None => interpolator.expand(word, raw),
+            },
+            Some((Operator::Required, word)) => match non_empty {
+                Some(value) => Ok(value),
+                None => {
+                    let message = interpolator.expand(word, false)?;
+
+                    if message.is_empty() {
+                        Err(format!("environment variable '{name}' not found"))
+                    } else {
+                        Err(format!("{name}: {message}"))
+                    }
+                }
+            },
+            Some((Operator::Alternate, word)) => match non_empty {
+                Some(_) => interpolator.expand(word, raw),
+                None => Ok(String::new()),
+            },
         }
     }
 }
@@ -100,28 +619,56 @@ mod tests {
     #[test]
     fn test_env_with_fallback() {
         unsafe { std::env::set_var("MY_VAR", "hello") };
-        let result = Interpolator::interpolate("value: ${MY_VAR:fallback}").unwrap();
+        let result = Interpolator::default()
+            .interpolate("value: ${MY_VAR:fallback}")
+            .unwrap();
         assert_eq!(result, "value: hello");
     }
 
     #[test]
     fn test_env_fallback_used() {
         unsafe { std::env::remove_var("MISSING_VAR") };
-        let result = Interpolator::interpolate("value: ${MISSING_VAR:default}").unwrap();
+        let result = Interpolator::default()
+            .interpolate("value: ${MISSING_VAR:default}")
+            .unwrap();
         assert_eq!(result, "value: default");
     }
 
+    #[test]
+    fn test_env_short_default_keeps_empty_value() {
+        unsafe { std::env::set_var("EMPTY_SHORT_VAR", "") };
+        let result = Interpolator::default()
+            .interpolate("[${EMPTY_SHORT_VAR:short}][${EMPTY_SHORT_VAR:-long}]")
+            .unwrap();
+        assert_eq!(result, "[][long]");
+    }
+
+    #[test]
+    fn test_file_references_in_one_pass() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        write!(tmp, "a\"b").unwrap();
+        let path = tmp.path().to_str().unwrap();
+        let result = Interpolator::default()
+            .interpolate(&format!(
+                "x = \"file:{path}\"\ny = \"file:{path}:d\"\nz = \"file:/no/existe:d\""
+            ))
+            .unwrap();
+        assert_eq!(result, "x = \"a\\\"b\"\ny = \"a\\\"b\"\nz = \"d\"");
+    }
+
     #[test]
     fn test_env_braced_exists() {
         unsafe { std::env::set_var("BRACED_VAR", "world") };
-        let result = Interpolator::interpolate("hello ${BRACED_VAR}").unwrap();
+        let result = Interpolator::default()
+            .interpolate("hello ${BRACED_VAR}")
+            .unwrap();
         assert_eq!(result, "hello world");
     }
 
     #[test]
     fn test_env_braced_missing_errors() {
         unsafe { std::env::remove_var("MISSING_VAR") };
-        let result = Interpolator::interpolate("${MISSING_VAR}");
+        let result = Interpolator::default().interpolate("${MISSING_VAR}");
         assert!(result.is_err());
     }
 
@@ -129,13 +676,17 @@ mod tests {
     fn test_dollar_without_braces_is_literal() {
         // $VAR ya no se interpola — debe quedar tal cual
         unsafe { std::env::set_var("PLAIN_VAR", "should_not_appear") };
-        let result = Interpolator::interpolate("hello $PLAIN_VAR").unwrap();
+        let result = Interpolator::default()
+            .interpolate("hello $PLAIN_VAR")
+            .unwrap();
         assert_eq!(result, "hello $PLAIN_VAR");
     }
 
     #[test]
     fn test_file_with_fallback() {
-        let result = Interpolator::interpolate("data: file:/no/existe:mi_default").unwrap();
+        let result = Interpolator::default()
+            .interpolate("data: file:/no/existe:mi_default")
+            .unwrap();
         assert_eq!(result, "data: mi_default");
     }
 
@@ -144,13 +695,265 @@ mod tests {
         let mut tmp = NamedTempFile::new().unwrap();
         write!(tmp, "contenido").unwrap();
         let path = tmp.path().to_str().unwrap();
-        let result = Interpolator::interpolate(&format!("data: file:{path}")).unwrap();
+        let result = Interpolator::default()
+            .interpolate(&format!("data: file:{path}"))
+            .unwrap();
         assert_eq!(result, "data: contenido");
     }
 
     #[test]
     fn test_file_missing_errors() {
-        let result = Interpolator::interpolate("file:/ruta/inexistente");
+        let result = Interpolator::default().interpolate("file:/ruta/inexistente");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_nested_default() {
+        unsafe { std::env::remove_var("NESTED_OUTER") };
+        unsafe { std::env::set_var("NESTED_INNER", "inner") };
+        let result = Interpolator::default()
+            .interpolate("${NESTED_OUTER:-${NESTED_INNER:x}}")
+            .unwrap();
+        assert_eq!(result, "inner");
+
+        unsafe { std::env::remove_var("NESTED_INNER") };
+        let result = Interpolator::default()
+            .interpolate("${NESTED_OUTER:${NESTED_INNER:-x}}!")
+            .unwrap();
+        assert_eq!(result, "x!");
+    }
+
+    #[test]
+    fn test_default_with_braces() {
+        unsafe { std::env::remove_var("BRACES_VAR") };
+        let result = Interpolator::default()
+            .interpolate(r#"${BRACES_VAR:-{"a": {}}}"#)
+            .unwrap();
+        assert_eq!(result, r#"{"a": {}}"#);
+    }
+
+    #[test]
+    fn test_unused_default_is_not_expanded() {
+        unsafe { std::env::set_var("LAZY_SET", "set") };
+        unsafe { std::env::remove_var("LAZY_MISSING") };
+        let result = Interpolator::default()
+            .interpolate("${LAZY_SET:-${LAZY_MISSING}}")
+            .unwrap();
+        assert_eq!(result, "set");
+    }
+
+    #[test]
+    fn test_required_with_message() {
+        unsafe { std::env::set_var("REQUIRED_EMPTY", "") };
+        let error = Interpolator::default()
+            .interpolate("${REQUIRED_EMPTY:?must be set}")
+            .unwrap_err();
+        assert_eq!(error, "REQUIRED_EMPTY: must be set");
+    }
+
+    #[test]
+    fn test_alternate_value() {
+        unsafe { std::env::set_var("ALT_SET", "1") };
+        unsafe { std::env::remove_var("ALT_MISSING") };
+        let result = Interpolator::default()
+            .interpolate("[${ALT_SET:+on}][${ALT_MISSING:+on}]")
+            .unwrap();
+        assert_eq!(result, "[on][]");
+    }
+
+    #[test]
+    fn test_escaped_expression_is_literal() {
+        unsafe { std::env::remove_var("ESCAPED_VAR") };
+        let result = Interpolator::default()
+            .interpolate("$${ESCAPED_VAR} ${ESCAPED_VAR:-$${x}}")
+            .unwrap();
+        assert_eq!(result, "${ESCAPED_VAR} ${x}");
+    }
+
+    #[test]
+    fn test_unterminated_expression_errors() {
+        assert!(
+            Interpolator::default()
+                .interpolate("${UNTERMINATED:-value")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_table_values_are_not_reparsed() {
+        unsafe { std::env::set_var("INJECTED_VAR", "x\"\n[admin]\nenabled = true") };
+        let mut table = toml::from_str::<Table>("name = \"${INJECTED_VAR}\"").unwrap();
+        Interpolator::default()
+            .interpolate_table(&mut table)
+            .unwrap();
+
+        assert_eq!(table.len(), 1);
+        assert_eq!(table["name"].as_str(), Some("x\"\n[admin]\nenabled = true"));
+    }
+
+    #[test]
+    fn test_table_file_reference_must_be_whole_value() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        write!(tmp, "secret").unwrap();
+        let path = tmp.path().to_str().unwrap();
+
+        let mut table = Table::new();
+        table.insert("key".into(), Value::String(format!("file:{path}")));
+        table.insert("text".into(), Value::String(format!("see file:{path}")));
+        table.insert(
+            "missing".into(),
+            Value::String("file:/no/existe:fallback".into()),
+        );
+        Interpolator::default()
+            .interpolate_table(&mut table)
+            .unwrap();
+
+        assert_eq!(table["key"].as_str(), Some("secret"));
+        assert_eq!(
+            table["text"].as_str(),
+            Some(format!("see file:{path}").as_str())
+        );
+        assert_eq!(table["missing"].as_str(), Some("fallback"));
+    }
+
+    #[test]
+    fn test_table_file_reference_is_not_read_from_expanded_values() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        write!(tmp, "secret").unwrap();
+        let path = tmp.path().to_str().unwrap();
+
+        unsafe {
+            std::env::set_var("FILE_INJECTION_VAR", format!("file:{path}"));
+            std::env::set_var("FILE_INJECTION_PATH", path);
+        }
+
+        let mut table = Table::new();
+        table.insert(
+            "input".into(),
+            Value::String("${FILE_INJECTION_VAR}".into()),
+        );
+        table.insert(
+            "written".into(),
+            Value::String("file:${FILE_INJECTION_PATH}".into()),
+        );
+        Interpolator::default()
+            .interpolate_table(&mut table)
+            .unwrap();
+
+        assert_eq!(
+            table["input"].as_str(),
+            Some(format!("file:{path}").as_str())
+        );
+        assert_eq!(table["written"].as_str(), Some("secret"));
+    }
+
+    #[test]
+    fn test_custom_resolver() {
+        let mut interpolator = Interpolator::default();
+        interpolator.register("base64", |argument: &str| match argument {
+            "aGVsbG8=" => Ok(Some("hello".to_string())),
+            _ => Err(format!("invalid base64 '{argument}'")),
+        });
+
+        unsafe { std::env::set_var("RESOLVER_ENCODED", "aGVsbG8=") };
+        let result = interpolator
+            .interpolate("${base64:${RESOLVER_ENCODED}}")
+            .unwrap();
+        assert_eq!(result, "hello");
+        assert!(interpolator.interpolate("${base64:???}").is_err());
+    }
+
+    #[test]
+    fn test_resolver_output_is_escaped_in_raw_text() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        writeln!(tmp, "a\"b\\").unwrap();
+        let path = tmp.path().to_str().unwrap();
+
+        let mut interpolator = Interpolator::default();
+        interpolator.register("vault", |_: &str| Ok(Some("x\"\n[admin]".to_string())));
+
+        let content = interpolator
+            .interpolate(&format!(
+                "file = \"${{file:{path}}}\"\nvault = \"${{vault:db}}\""
+            ))
+            .unwrap();
+        let table = toml::from_str::<Table>(&content).unwrap();
+
+        assert_eq!(table["file"].as_str(), Some("a\"b\\\n"));
+        assert_eq!(table["vault"].as_str(), Some("x\"\n[admin]"));
+        assert_eq!(table.len(), 2);
+
+        let mut table = Table::new();
+        table.insert("vault".into(), Value::String("${vault:db}".into()));
+        interpolator.interpolate_table(&mut table).unwrap();
+
+        assert_eq!(table["vault"].as_str(), Some("x\"\n[admin]"));
+    }
+
+    #[test]
+    fn test_env_resolver_can_be_replaced() {
+        let mut interpolator = Interpolator::default();
+        interpolator.register("env", |name: &str| {
+            Ok((name == "MOCKED").then(|| "mocked".to_string()))
+        });
+
+        let result = interpolator
+            .interpolate("${MOCKED} ${env:MOCKED} ${OTHER:-fallback}")
+            .unwrap();
+        assert_eq!(result, "mocked mocked fallback");
+    }
+
+    #[test]
+    fn test_unregistered_scheme_is_literal() {
+        let result = Interpolator::default()
+            .interpolate("${secret-store:db}")
+            .unwrap();
+        assert_eq!(result, "${secret-store:db}");
+    }
+
+    #[test]
+    fn test_references_are_deferred() {
+        let result = Interpolator::default()
+            .interpolate("${config:server.host} $${config:server.host} ${UNSET_REF:-${config:a}}")
+            .unwrap();
+        assert_eq!(
+            result,
+            "${config:server.host} $${config:server.host} ${config:a}"
+        );
+    }
+
+    #[test]
+    fn test_resolve_references() {
+        let mut table = toml::from_str::<Table>(
+            r#"
+            base_url = "http://${config:server.host}:${config:server.port}"
+            port = "${config:server.port}"
+            literal = "$${config:server.host}"
+            chained = "${config:base_url}/v1"
+
+            [server]
+            host = "localhost"
+            port = 8080
+            "#,
+        )
+        .unwrap();
+
+        resolve_references(&mut table, &|_| false).unwrap();
+
+        assert_eq!(table["base_url"].as_str(), Some("http://localhost:8080"));
+        assert_eq!(table["port"].as_integer(), Some(8080));
+        assert_eq!(table["literal"].as_str(), Some("${config:server.host}"));
+        assert_eq!(table["chained"].as_str(), Some("http://localhost:8080/v1"));
+    }
+
+    #[test]
+    fn test_resolve_references_detects_cycles() {
+        let mut table =
+            toml::from_str::<Table>("a = \"${config:b}\"\nb = \"x${config:a}\"").unwrap();
+        let error = resolve_references(&mut table, &|_| false).unwrap_err();
+        assert_eq!(error, "cyclic config reference: a -> b -> a");
+
+        let mut table = toml::from_str::<Table>("a = \"${config:missing}\"").unwrap();
+        assert!(resolve_references(&mut table, &|_| false).is_err());
+    }
 }
@@ -1,18 +1,37 @@
 mod builder;
+mod coerce;
 mod config;
 mod error;
+mod format;
+mod glob;
 mod interpolation;
+mod merge;
+mod provenance;
+mod redaction;
+#[cfg(feature = "reload")]
+mod reload;
 mod utils;
 
+pub mod schema;
+
 use serde::de::DeserializeOwned;
 
 pub use builder::ConfigBuilder;
 pub use config::{Config, ConfigSourceInfo};
 pub use error::ConfigError;
+pub use format::FileFormat;
+pub use interpolation::{InterpolationMode, Resolver};
+pub use merge::ArrayMerge;
+pub use provenance::{KeyOrigin, Location, ShadowedValue};
+pub use redaction::{REDACTED, Redaction};
+pub use utils::secret::Secret;
 
 #[cfg(feature = "macros")]
 pub use thisconfig_macros::config;
 
+#[cfg(feature = "reload")]
+pub use reload::{ReloadStatus, ReloadableConfig, WatchHandle};
+
 #[cfg(feature = "byte-unit")]
 pub use utils::byte_unit::ByteConfig;
 
@@ -33,5 +52,14 @@ pub trait ConfigItem: DeserializeOwned + Clone + Send + Sync + 'static {
     /// host = "localhost"
     /// ```
     /// In this example, the `key()` method for `DatabaseConfig` would return `"database"`.
+    /// Nested sections use a dotted path, e.g. `"services.billing.db"`.
     fn key() -> &'static str;
+
+    /// Describes the fields of this section for schema and sample file export.
+    ///
+    /// Implemented by the `#[config]` macro; manual implementations default to an
+    /// empty field list.
+    fn schema() -> schema::SectionSchema {
+        schema::SectionSchema::new(Self::key())
+    }
 }
@@ -0,0 +1,343 @@
+use crate::{
+    ConfigSourceInfo, KeyOrigin, glob,
+    provenance::{Locations, Provenance},
+};
+use toml::{Table, Value};
+
+/// How an array is combined with the array of the same key from an earlier source.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ArrayMerge {
+    /// The later array replaces the earlier one.
+    #[default]
+    Replace,
+    /// Items of the later array are added after the earlier ones.
+    Append,
+    /// Items of the later array are added before the earlier ones.
+    Prepend,
+    /// Arrays of tables are matched by the value of the given field: matching
+    /// tables are deep-merged in place, the rest are appended.
+    MergeBy(String),
+}
+
+/// Rules applied when merging each source into the configuration.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MergeOptions {
+    arrays: ArrayMerge,
+    /// Strategies for specific dotted key paths or glob patterns; the last match wins.
+    array_paths: Vec<(String, ArrayMerge)>,
+    /// Name of the top-level directive removing keys, disabled when `None`.
+    unset_key: Option<String>,
+}
+
+impl MergeOptions {
+    pub(crate) fn set_unset_key(&mut self, key: &str) {
+        self.unset_key = Some(key.to_string());
+    }
+
+    /// Merges the `layer` of a source into `base`, recording provenance.
+    ///
+    /// Keys listed in the layer's unset directive are removed from `base` first,
+    /// so a layer can also replace a whole table by unsetting and redefining it.
+    pub(crate) fn apply(
+        &self,
+        base: &mut Table,
+        provenance: &mut Provenance,
+        mut layer: Table,
+        source: &ConfigSourceInfo,
+        locations: &Locations,
+    ) {
+        let origin = KeyOrigin {
+            source: source.clone(),
+            location: self
+                .unset_key
+                .as_ref()
+                .and_then(|key| locations.get(key))
+                .copied(),
+        };
+
+        for path in self.take_unset(&mut layer) {
+            if let Some(removed) = Self::remove(base, &path) {
+                provenance.remove(&path, &removed, &origin);
+            }
+        }
+
+        let merges_array = |path: &str| *self.array_strategy(path) != ArrayMerge::Replace;
+
+        provenance.record(base, &layer, source, locations, &merges_array);
+        self.merge(base, layer);
+    }
+
+    /// Takes the unset directive out of `layer` when it is enabled and an array of strings.
+    fn take_unset(&self, layer: &mut Table) -> Vec<String> {
+        let Some(unset_key) = &self.unset_key else {
+            return Vec::new();
+        };
+
+        let paths = match layer.get(unset_key) {
+            Some(Value::Array(items)) => items
+                .iter()
+                .map(|item| item.as_str().map(str::to_string))
+                .collect::<Option<Vec<_>>>(),
+            _ => None,
+        };
+
+        if paths.is_some() {
+            layer.remove(unset_key);
+        }
+
+        paths.unwrap_or_default()
+    }
+
+    /// Removes the value at a dotted path. An exact top-level key wins.
+    fn remove(table: &mut Table, path: &str) -> Option<Value> {
+        if let Some(value) = table.remove(path) {
+            return Some(value);
+        }
+
+        let (parent, key) = path.rsplit_once('.')?;
+        let mut current = table;
+
+        for segment in parent.split('.') {
+            current = current.get_mut(segment)?.as_table_mut()?;
+        }
+
+        current.remove(key)
+    }
+
+    pub(crate) fn set_arrays(&mut self, strategy: ArrayMerge) {
+        self.arrays = strategy;
+    }
+
+    pub(crate) fn set_arrays_at(&mut self, path: &str, strategy: ArrayMerge) {
+        self.array_paths.push((path.to_string(), strategy));
+    }
+
+    /// Deep-merges `other` into `base`. Tables are merged key by key, arrays follow
+    /// the configured strategy and any other value replaces the previous one.
+    pub(crate) fn merge(&self, base: &mut Table, other: Table) {
+        self.merge_table(base, other, "");
+    }
+
+    fn merge_table(&self, base: &mut Table, other: Table, prefix: &str) {
+        for (key, value) in other {
+            let path = match prefix {
+                "" => key.clone(),
+                _ => format!("{prefix}.{key}"),
+            };
+
+            match (base.get_mut(&key), value) {
+                (Some(Value::Table(base_table)), Value::Table(other_table)) => {
+                    self.merge_table(base_table, other_table, &path);
+                }
+                (Some(Value::Array(base_items)), Value::Array(other_items)) => {
+                    self.merge_array(base_items, other_items, &path);
+                }
+                (_, value) => {
+                    base.insert(key, value);
+                }
+            }
+        }
+    }
+
+    fn merge_array(&self, base: &mut Vec<Value>, other: Vec<Value>, path: &str) {
+        match self.array_strategy(path) {
+            ArrayMerge::Replace => *base = other,
+            ArrayMerge::Append => base.extend(other),
+            ArrayMerge::Prepend => {
+                base.splice(0..0, other);
+            }
+            ArrayMerge::MergeBy(field) => {
+                for item in other {
+                    let existing = item
+                        .get(field.as_str())
+                        .and_then(|id| base.iter_mut().find(|b| b.get(field.as_str()) == Some(id)));
+
+                    match (existing, item) {
+                        (Some(Value::Table(existing)), Value::Table(item)) => {
+                            self.merge_table(existing, item, path);
+                        }
+                        (_, item) => base.push(item),
+                    }
+                }
+            }
+        }
+    }
+
+    fn array_strategy(&self, path: &str) -> &ArrayMerge {
+        self.array_paths
+            .iter()
+            .rev()
+            .find(|(pattern, _)| glob::matches(pattern, path, '.'))
+            .map_or(&self.arrays, |(_, strategy)| strategy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merged(options: &MergeOptions, base: &str, layer: &str) -> Table {
+        let mut table = toml::from_str::<Table>(base).expect("valid base");
+        options.merge(&mut table, toml::from_str(layer).expect("valid layer"));
+        table
+    }
+
+    #[test]
+    fn test_unset_directive_removes_keys() {
+        let mut options = MergeOptions::default();
+        options.set_unset_key("unset");
+        let mut provenance = Provenance::default();
+        let mut table = Table::new();
+
+        let base = toml::from_str::<Table>(
+            "[cache]\nttl = 5\n[db]\nhost = \"a\"\nreplica = \"b\"\n[db.pool]\nsize = 1",
+        )
+        .expect("valid base");
+        let layer = toml::from_str::<Table>(
+            "unset = [\"cache\", \"db.replica\", \"db.pool\", \"missing.key\"]\n\
+             [db.pool]\nmax = 4",
+        )
+        .expect("valid layer");
+
+        let source = ConfigSourceInfo::TomlString;
+        options.apply(
+            &mut table,
+            &mut provenance,
+            base,
+            &source,
+            &Locations::new(),
+        );
+        options.apply(
+            &mut table,
+            &mut provenance,
+            layer,
+            &source,
+            &Locations::new(),
+        );
+
+        assert!(!table.contains_key("cache"));
+        assert!(!table.contains_key("unset"));
+        assert_eq!(table["db"]["host"].as_str(), Some("a"));
+        assert!(table["db"].get("replica").is_none());
+        assert_eq!(table["db"]["pool"].as_table().map(Table::len), Some(1));
+
+        assert!(provenance.origin("cache.ttl").is_none());
+        assert!(provenance.origin("db.pool.max").is_some());
+
+        let removed = provenance
+            .shadowed()
+            .iter()
+            .map(|value| value.key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(removed, vec!["cache.ttl", "db.replica", "db.pool.size"]);
+    }
+
+    #[test]
+    fn test_unset_directive_is_opt_in() {
+        let table = merged(
+            &MergeOptions::default(),
+            "unset = [\"a\"]\n[cache]\nttl = 5",
+            "unset = [\"cache\"]",
+        );
+
+        assert_eq!(table["unset"], Value::Array(vec!["cache".into()]));
+        assert_eq!(table["cache"]["ttl"].as_integer(), Some(5));
+    }
+
+    #[test]
+    fn test_array_strategies() {
+        let mut options = MergeOptions::default();
+        let base = "tags = [\"a\"]\n[nested]\ntags = [\"a\"]";
+        let layer = "tags = [\"b\"]\n[nested]\ntags = [\"b\"]";
+
+        let table = merged(&options, base, layer);
+        assert_eq!(table["tags"], Value::Array(vec!["b".into()]));
+
+        options.set_arrays(ArrayMerge::Append);
+        options.set_arrays_at("nested.tags", ArrayMerge::Prepend);
+
+        let table = merged(&options, base, layer);
+        assert_eq!(table["tags"], Value::Array(vec!["a".into(), "b".into()]));
+        assert_eq!(
+            table["nested"]["tags"],
+            Value::Array(vec!["b".into(), "a".into()])
+        );
+    }
+
+    #[test]
+    fn test_appended_arrays_keep_every_origin() {
+        let mut options = MergeOptions::default();
+        let mut provenance = Provenance::default();
+        let mut table = Table::new();
+
+        options.set_arrays_at("tags", ArrayMerge::Append);
+
+        let layers = [
+            (
+                "tags = [\"a\"]\nhosts = [\"x\"]",
+                ConfigSourceInfo::TomlString,
+            ),
+            (
+                "tags = [\"b\"]\nhosts = [\"y\"]",
+                ConfigSourceInfo::Environment {
+                    prefix: "APP".to_string(),
+                    separator: "__".to_string(),
+                },
+            ),
+        ];
+
+        for (layer, source) in &layers {
+            let layer = toml::from_str::<Table>(layer).expect("valid layer");
+            options.apply(
+                &mut table,
+                &mut provenance,
+                layer,
+                source,
+                &Locations::new(),
+            );
+        }
+
+        let sources = |key| {
+            provenance
+                .origins(key)
+                .into_iter()
+                .map(|origin| origin.source.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            sources("tags"),
+            vec![layers[0].1.clone(), layers[1].1.clone()]
+        );
+        assert_eq!(sources("hosts"), vec![layers[1].1.clone()]);
+
+        let shadowed = provenance
+            .shadowed()
+            .iter()
+            .map(|value| value.key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(shadowed, vec!["hosts"]);
+    }
+
+    #[test]
+    fn test_merge_arrays_of_tables_by_field() {
+        let mut options = MergeOptions::default();
+        options.set_arrays_at("**.upstreams", ArrayMerge::MergeBy("name".to_string()));
+
+        let table = merged(
+            &options,
+            "[[proxy.upstreams]]\nname = \"api\"\nport = 80\n\
+             [[proxy.upstreams]]\nname = \"web\"\nport = 81",
+            "[[proxy.upstreams]]\nname = \"api\"\nport = 8080\n\
+             [[proxy.upstreams]]\nname = \"admin\"\nport = 82",
+        );
+
+        let upstreams = table["proxy"]["upstreams"].as_array().expect("array");
+        let ports = upstreams
+            .iter()
+            .map(|u| (u["name"].as_str().unwrap(), u["port"].as_integer().unwrap()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(ports, vec![("api", 8080), ("web", 81), ("admin", 82)]);
+    }
+}
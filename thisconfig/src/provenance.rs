@@ -0,0 +1,229 @@
+use crate::ConfigSourceInfo;
+use std::{collections::HashMap, fmt};
+use toml::{Table, Value, de::DeTable, de::DeValue};
+
+/// One-based line and column of a key inside its source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// The source that supplied a configuration value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyOrigin {
+    pub source: ConfigSourceInfo,
+    /// Position of the key, available for TOML text sources.
+    pub location: Option<Location>,
+}
+
+/// A value that was overwritten or unset by a later source during merging.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShadowedValue {
+    pub key: String,
+    pub value: Value,
+    pub origin: KeyOrigin,
+    pub shadowed_by: KeyOrigin,
+}
+
+impl fmt::Display for KeyOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)?;
+
+        if let Some(Location { line, column }) = self.location {
+            write!(f, ":{line}:{column}")?;
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) type Locations = HashMap<String, Location>;
+
+/// Per-key provenance collected while merging sources.
+#[derive(Debug, Default)]
+pub(crate) struct Provenance {
+    origins: HashMap<String, KeyOrigin>,
+    /// Earlier sources whose items are still part of a merged array, in merge order.
+    merged: HashMap<String, Vec<KeyOrigin>>,
+    shadowed: Vec<ShadowedValue>,
+}
+
+impl Provenance {
+    pub(crate) fn origin(&self, key: &str) -> Option<&KeyOrigin> {
+        self.origins.get(key)
+    }
+
+    /// Returns every source that contributed to the value at `key`, in merge order.
+    pub(crate) fn origins(&self, key: &str) -> Vec<&KeyOrigin> {
+        self.merged
+            .get(key)
+            .into_iter()
+            .flatten()
+            .chain(self.origins.get(key))
+            .collect()
+    }
+
+    pub(crate) fn shadowed(&self) -> &[ShadowedValue] {
+        &self.shadowed
+    }
+
+    /// Records the leaf values of `layer` before it is merged into `base`.
+    ///
+    /// Arrays at paths for which `merges_array` holds keep the items of earlier
+    /// sources, so those sources stay listed as origins instead of being shadowed.
+    pub(crate) fn record(
+        &mut self,
+        base: &Table,
+        layer: &Table,
+        source: &ConfigSourceInfo,
+        locations: &Locations,
+        merges_array: &dyn Fn(&str) -> bool,
+    ) {
+        self.record_table(base, layer, "", source, locations, merges_array);
+    }
+
+    /// Records the removal of `value`, previously stored at `path`, by an unset directive.
+    pub(crate) fn remove(&mut self, path: &str, value: &Value, removed_by: &KeyOrigin) {
+        self.shadow(path, value, removed_by);
+    }
+
+    fn record_table(
+        &mut self,
+        base: &Table,
+        layer: &Table,
+        prefix: &str,
+        source: &ConfigSourceInfo,
+        locations: &Locations,
+        merges_array: &dyn Fn(&str) -> bool,
+    ) {
+        for (key, value) in layer {
+            let path = join(prefix, key);
+
+            match (base.get(key), value) {
+                (Some(Value::Table(base_table)), Value::Table(layer_table)) => {
+                    self.record_table(
+                        base_table,
+                        layer_table,
+                        &path,
+                        source,
+                        locations,
+                        merges_array,
+                    );
+                }
+                (Some(Value::Array(_)), Value::Array(_)) if merges_array(&path) => {
+                    let origin = KeyOrigin {
+                        source: source.clone(),
+                        location: locations.get(&path).copied(),
+                    };
+
+                    if let Some(previous) = self.origins.insert(path.clone(), origin) {
+                        self.merged.entry(path).or_default().push(previous);
+                    }
+                }
+                (existing, value) => {
+                    let origin = KeyOrigin {
+                        source: source.clone(),
+                        location: locations.get(&path).copied(),
+                    };
+
+                    if let Some(existing) = existing {
+                        self.shadow(&path, existing, &origin);
+                    }
+
+                    self.insert(&path, value, source, locations);
+                }
+            }
+        }
+    }
+
+    fn insert(
+        &mut self,
+        path: &str,
+        value: &Value,
+        source: &ConfigSourceInfo,
+        locations: &Locations,
+    ) {
+        match value {
+            Value::Table(table) => {
+                for (key, value) in table {
+                    self.insert(&join(path, key), value, source, locations);
+                }
+            }
+            _ => {
+                let origin = KeyOrigin {
+                    source: source.clone(),
+                    location: locations.get(path).copied(),
+                };
+
+                self.origins.insert(path.to_string(), origin);
+            }
+        }
+    }
+
+    fn shadow(&mut self, path: &str, existing: &Value, shadowed_by: &KeyOrigin) {
+        match existing {
+            Value::Table(table) => {
+                for (key, value) in table {
+                    self.shadow(&join(path, key), value, shadowed_by);
+                }
+            }
+            _ => {
+                self.merged.remove(path);
+
+                if let Some(origin) = self.origins.remove(path) {
+                    self.shadowed.push(ShadowedValue {
+                        key: path.to_string(),
+                        value: existing.clone(),
+                        origin,
+                        shadowed_by: shadowed_by.clone(),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Collects the position of every key in a TOML document, keyed by dotted path.
+pub(crate) fn locate(content: &str) -> Locations {
+    let mut locations = Locations::new();
+
+    if let Ok(table) = DeTable::parse(content) {
+        locate_table(content, table.get_ref(), "", &mut locations);
+    }
+
+    locations
+}
+
+fn locate_table(content: &str, table: &DeTable<'_>, prefix: &str, locations: &mut Locations) {
+    for (key, value) in table {
+        let path = join(prefix, key.get_ref());
+        let offset = key.span().start;
+
+        let before = &content[..offset.min(content.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before
+            .rsplit('\n')
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .count()
+            + 1;
+
+        locations
+            .entry(path.clone())
+            .or_insert(Location { line, column });
+
+        if let DeValue::Table(nested) = value.get_ref() {
+            locate_table(content, nested, &path, locations);
+        }
+    }
+}
+
+fn join(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{prefix}.{key}")
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{ConfigItem, glob, schema::FieldSchema};
+use toml::{Table, Value};
+
+/// Placeholder written in place of redacted values.
+pub const REDACTED: &str = "***";
+
+/// Rules selecting the keys hidden when the effective configuration is exported.
+///
+/// Keys are matched by their dotted path. In patterns, `*` matches within one
+/// segment and `**` matches any number of segments, so `*.password` hides
+/// `db.password` while `**.password` also hides `services.billing.db.password`.
+///
+/// # Example
+///
+/// ```ignore
+/// let redaction = Redaction::new()
+///     .key("**.password")
+///     .key("*.api_key")
+///     .secrets::<DatabaseConfig>();
+///
+/// tracing::info!("Effective configuration:\n{}", config.to_toml_string(&redaction)?);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Redaction {
+    patterns: Vec<String>,
+}
+
+impl Redaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Redacts every key matching a glob pattern.
+    pub fn key(mut self, pattern: impl Into<String>) -> Self {
+        self.patterns.push(pattern.into());
+        self
+    }
+
+    /// Redacts the `Secret<T>` fields of a `#[config]` section, based on its schema,
+    /// including those of nested `#[config]` sections.
+    pub fn secrets<T: ConfigItem>(mut self) -> Self {
+        let schema = T::schema();
+
+        self.add_secrets(schema.key, &schema.fields);
+        self
+    }
+
+    fn add_secrets(&mut self, prefix: &str, fields: &[FieldSchema]) {
+        for field in fields {
+            let path = format!("{prefix}.{}", field.name);
+
+            self.add_secrets(&path, &field.fields);
+
+            if field.secret {
+                self.patterns.push(path);
+            }
+        }
+    }
+
+    /// Returns whether the key at `path` is hidden by these rules.
+    pub fn is_redacted(&self, path: &str) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| glob::matches(pattern, path, '.'))
+    }
+
+    /// Returns a copy of `table` with redacted keys replaced by [`REDACTED`].
+    pub(crate) fn apply(&self, table: &Table) -> Table {
+        self.apply_table(table, "")
+    }
+
+    fn apply_table(&self, table: &Table, prefix: &str) -> Table {
+        table
+            .iter()
+            .map(|(key, value)| {
+                let path = match prefix {
+                    "" => key.clone(),
+                    _ => format!("{prefix}.{key}"),
+                };
+
+                (key.clone(), self.apply_value(value, &path))
+            })
+            .collect()
+    }
+
+    fn apply_value(&self, value: &Value, path: &str) -> Value {
+        if self.is_redacted(path) {
+            return Value::String(REDACTED.to_string());
+        }
+
+        match value {
+            Value::Table(table) => Value::Table(self.apply_table(table, path)),
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|item| self.apply_value(item, path))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+}
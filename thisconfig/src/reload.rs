@@ -0,0 +1,296 @@
+use crate::{Config, ConfigBuilder, ConfigError};
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::{Duration, SystemTime},
+};
+use tokio::sync::watch;
+use tracing::{error, info};
+
+/// Outcome of the reload attempts performed so far.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReloadStatus {
+    /// Number of successful reloads after the initial build.
+    pub reloads: u64,
+    /// Time of the last reload attempt, successful or not.
+    pub last_attempt: Option<SystemTime>,
+    /// Error of the last attempt, cleared by the next successful reload.
+    pub last_error: Option<String>,
+    /// [`ConfigError::summary`] of `last_error`, free of configuration values.
+    pub last_error_summary: Option<String>,
+}
+
+/// State of a watched path: modification time, length and, for a directory,
+/// the sorted names of its entries.
+#[derive(Debug, PartialEq, Eq)]
+struct Stamp {
+    modified: SystemTime,
+    len: u64,
+    entries: Vec<OsString>,
+}
+
+impl Stamp {
+    fn read(path: &Path) -> Option<Self> {
+        let meta = fs::metadata(path).ok()?;
+
+        let mut entries = if meta.is_dir() {
+            fs::read_dir(path)
+                .ok()?
+                .filter_map(|entry| entry.ok().map(|entry| entry.file_name()))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        entries.sort();
+
+        Some(Self {
+            modified: meta.modified().ok()?,
+            len: meta.len(),
+            entries,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Shared {
+    builder: ConfigBuilder,
+    sender: watch::Sender<Config>,
+    status: Mutex<ReloadStatus>,
+}
+
+/// A configuration handle that can be rebuilt from its original sources.
+///
+/// Subscribers are notified through a [`watch`] channel every time a reload
+/// succeeds. A failed reload keeps the previous configuration in place.
+#[derive(Debug, Clone)]
+pub struct ReloadableConfig {
+    shared: Arc<Shared>,
+}
+
+impl ReloadableConfig {
+    pub(crate) fn new(builder: ConfigBuilder) -> Result<Self, ConfigError> {
+        let config = builder.clone().build()?;
+        let (sender, _) = watch::channel(config);
+
+        Ok(Self {
+            shared: Arc::new(Shared {
+                builder,
+                sender,
+                status: Mutex::new(ReloadStatus::default()),
+            }),
+        })
+    }
+
+    /// Returns the currently active configuration.
+    pub fn current(&self) -> Config {
+        self.shared.sender.borrow().clone()
+    }
+
+    /// Returns a receiver that observes every successfully reloaded configuration.
+    pub fn subscribe(&self) -> watch::Receiver<Config> {
+        self.shared.sender.subscribe()
+    }
+
+    /// Returns the outcome of the reload attempts performed so far.
+    pub fn status(&self) -> ReloadStatus {
+        self.lock_status().clone()
+    }
+
+    /// Rebuilds the configuration from the original sources and swaps it in.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError` if the rebuild fails. The previous configuration stays active.
+    pub fn reload(&self) -> Result<(), ConfigError> {
+        let result = self.shared.builder.clone().build();
+        let mut status = self.lock_status();
+
+        status.last_attempt = Some(SystemTime::now());
+
+        match result {
+            Ok(config) => {
+                status.reloads += 1;
+                status.last_error = None;
+                status.last_error_summary = None;
+                drop(status);
+
+                self.shared.sender.send_replace(config);
+                info!("Configuration reloaded");
+
+                Ok(())
+            }
+            Err(e) => {
+                error!("Configuration reload failed, keeping previous config: {e}");
+                status.last_error = Some(e.to_string());
+                status.last_error_summary = Some(e.summary());
+
+                Err(e)
+            }
+        }
+    }
+
+    /// Polls every file and directory the active configuration was loaded from, or
+    /// looked for, every `interval` and reloads when any of them is created, removed
+    /// or modified. Included files, directory listings and search candidates count.
+    ///
+    /// Polling stops when the returned handle is dropped.
+    pub fn watch(&self, interval: Duration) -> WatchHandle {
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = WatchHandle { stop: stop.clone() };
+        let config = self.clone();
+        let mut snapshot = config.snapshot();
+
+        thread::spawn(move || {
+            while !stop.load(Ordering::Relaxed) {
+                thread::sleep(interval);
+
+                let current = config.snapshot();
+
+                if current != snapshot {
+                    let _ = config.reload();
+                    snapshot = config.snapshot();
+                }
+            }
+        });
+
+        handle
+    }
+
+    fn snapshot(&self) -> HashMap<PathBuf, Option<Stamp>> {
+        self.current()
+            .watched
+            .iter()
+            .map(|path| (path.clone(), Stamp::read(path)))
+            .collect()
+    }
+
+    fn lock_status(&self) -> std::sync::MutexGuard<'_, ReloadStatus> {
+        self.shared
+            .status
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Stops the file watcher started by [`ReloadableConfig::watch`] when dropped.
+#[derive(Debug)]
+pub struct WatchHandle {
+    stop: Arc<AtomicBool>,
+}
+
+impl Drop for WatchHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::time::Instant;
+
+    #[derive(Debug, Clone, Deserialize)]
+    struct Limits {
+        rate: u32,
+    }
+
+    impl crate::ConfigItem for Limits {
+        fn key() -> &'static str {
+            "limits"
+        }
+    }
+
+    #[test]
+    fn test_reload_swaps_config_and_notifies() {
+        let temp_file = tempfile::NamedTempFile::new().expect("failed to create temp file");
+        let path = temp_file.path();
+        fs::write(path, "[limits]\nrate = 10").expect("failed to write");
+
+        let config = Config::builder()
+            .add_required_file(path)
+            .build_reloadable()
+            .expect("failed to build config");
+
+        let mut receiver = config.subscribe();
+
+        fs::write(path, "[limits]\nrate = 20").expect("failed to write");
+        config.reload().expect("failed to reload");
+
+        assert!(receiver.has_changed().expect("sender dropped"));
+        assert_eq!(receiver.borrow_and_update().expect::<Limits>().rate, 20);
+        assert_eq!(config.current().expect::<Limits>().rate, 20);
+        assert_eq!(config.status().reloads, 1);
+    }
+
+    #[test]
+    fn test_failed_reload_keeps_previous_config() {
+        let temp_file = tempfile::NamedTempFile::new().expect("failed to create temp file");
+        let path = temp_file.path();
+        fs::write(path, "[limits]\nrate = 10").expect("failed to write");
+
+        let config = Config::builder()
+            .add_required_file(path)
+            .build_reloadable()
+            .expect("failed to build config");
+
+        fs::write(path, "[limits\nrate = ").expect("failed to write");
+
+        assert!(config.reload().is_err());
+        assert_eq!(config.current().expect::<Limits>().rate, 10);
+        assert!(config.status().last_error.is_some());
+    }
+
+    #[test]
+    fn test_watch_reloads_on_file_change() {
+        let temp_file = tempfile::NamedTempFile::new().expect("failed to create temp file");
+        let path = temp_file.path();
+        fs::write(path, "[limits]\nrate = 10").expect("failed to write");
+
+        let config = Config::builder()
+            .add_required_file(path)
+            .build_reloadable()
+            .expect("failed to build config");
+
+        let _handle = config.watch(Duration::from_millis(10));
+        thread::sleep(Duration::from_millis(50));
+        fs::write(path, "[limits]\nrate = 30").expect("failed to write");
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+
+        while config.current().get::<Limits>().map(|limits| limits.rate) != Some(30) {
+            assert!(Instant::now() < deadline, "config was not reloaded");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_watch_reloads_on_new_file_in_dir() {
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        let root = dir.path();
+        fs::write(root.join("10-base.toml"), "[limits]\nrate = 10").expect("failed to write");
+
+        let config = Config::builder()
+            .add_required_dir(root)
+            .build_reloadable()
+            .expect("failed to build config");
+
+        let _handle = config.watch(Duration::from_millis(10));
+        fs::write(root.join("20-override.toml"), "[limits]\nrate = 30").expect("failed to write");
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+
+        while config.current().get::<Limits>().map(|limits| limits.rate) != Some(30) {
+            assert!(Instant::now() < deadline, "config was not reloaded");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
@@ -0,0 +1,668 @@
+use crate::{ConfigItem, Secret};
+use serde::Serialize;
+use std::{fmt::Write, marker::PhantomData};
+use toml::Value;
+
+/// Description of a configuration section, generated by the `#[config]` macro.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SectionSchema {
+    pub key: &'static str,
+    pub doc: Option<&'static str>,
+    pub fields: Vec<FieldSchema>,
+    /// Set by `#[serde(deny_unknown_fields)]`.
+    pub deny_unknown_fields: bool,
+}
+
+impl SectionSchema {
+    pub fn new(key: &'static str) -> Self {
+        Self {
+            key,
+            ..Self::default()
+        }
+    }
+}
+
+/// Description of a single field of a configuration section.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldSchema {
+    /// Key of the field in the configuration file, after serde renames.
+    pub name: String,
+    /// Rust type as written in the struct, e.g. `Option<Vec<String>>`.
+    pub ty: &'static str,
+    pub doc: Option<&'static str>,
+    /// Whether the field must be present in the configuration file.
+    pub required: bool,
+    pub default: Option<Value>,
+    /// Constraints taken from `#[validate(...)]` attributes.
+    pub constraints: Vec<Constraint>,
+    /// Whether the field holds a `Secret<T>` or `Option<Secret<T>>`, through aliases too.
+    pub secret: bool,
+    /// Fields of the field's type when it is itself a `#[config]` section.
+    pub fields: Vec<FieldSchema>,
+}
+
+/// A `validator` constraint attached to a field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Constraint {
+    Range {
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    Length {
+        min: Option<u64>,
+        max: Option<u64>,
+    },
+    Email,
+    Url,
+    /// Any other validator, kept by name.
+    Other(&'static str),
+}
+
+/// Collection of configuration sections used to export a JSON Schema or a sample file.
+///
+/// # Example
+///
+/// ```ignore
+/// let sample = ConfigSchema::new()
+///     .section::<ServerConfig>()
+///     .section::<DatabaseConfig>()
+///     .to_sample_toml();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ConfigSchema {
+    sections: Vec<SectionSchema>,
+}
+
+impl ConfigSchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the schema of a `ConfigItem` type.
+    pub fn section<T: ConfigItem>(mut self) -> Self {
+        self.sections.push(T::schema());
+        self
+    }
+
+    pub fn sections(&self) -> &[SectionSchema] {
+        &self.sections
+    }
+
+    /// Renders an annotated sample TOML file with defaults, types and constraints.
+    ///
+    /// Optional fields without a default are emitted commented out. Nested `#[config]`
+    /// sections are rendered as sub-tables after the other fields.
+    pub fn to_sample_toml(&self) -> String {
+        let mut out = String::new();
+
+        for section in &self.sections {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+
+            write_doc(&mut out, "", section.doc);
+            write_table(&mut out, "", section.key, &section.fields);
+        }
+
+        out
+    }
+
+    #[cfg(feature = "json")]
+    /// Renders a JSON Schema (draft 2020-12) describing every registered section.
+    pub fn to_json_schema(&self) -> String {
+        use serde_json::{Map, Value as Json, json};
+
+        let mut root = Map::new();
+
+        for section in &self.sections {
+            let mut schema = Json::Object(json_object(&section.fields));
+
+            if section.deny_unknown_fields {
+                schema["additionalProperties"] = Json::Bool(false);
+            }
+
+            if let Some(doc) = section.doc {
+                schema["description"] = doc.trim().into();
+            }
+
+            let mut segments = section.key.split('.').collect::<Vec<_>>();
+            let last = segments.pop().unwrap_or_default();
+            let mut parent = &mut root;
+
+            for segment in segments {
+                let entry = parent
+                    .entry(segment)
+                    .or_insert_with(|| json!({ "type": "object", "properties": {} }));
+
+                parent = entry["properties"]
+                    .as_object_mut()
+                    .expect("section parents are objects");
+            }
+
+            parent.insert(last.to_string(), schema);
+        }
+
+        let schema = json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": root,
+        });
+
+        serde_json::to_string_pretty(&schema).unwrap_or_default()
+    }
+}
+
+impl Constraint {
+    fn describe(&self) -> String {
+        let bounds = |min: Option<String>, max: Option<String>| {
+            let bounds = [("min", min), ("max", max)]
+                .into_iter()
+                .filter_map(|(name, value)| Some(format!("{name} = {}", value?)))
+                .collect::<Vec<_>>();
+
+            bounds.join(", ")
+        };
+
+        match self {
+            Self::Range { min, max } => format!(
+                "range({})",
+                bounds(min.map(|v| v.to_string()), max.map(|v| v.to_string()))
+            ),
+            Self::Length { min, max } => format!(
+                "length({})",
+                bounds(min.map(|v| v.to_string()), max.map(|v| v.to_string()))
+            ),
+            Self::Email => "email".to_string(),
+            Self::Url => "url".to_string(),
+            Self::Other(name) => (*name).to_string(),
+        }
+    }
+
+    #[cfg(feature = "json")]
+    fn apply(&self, property: &mut serde_json::Map<String, serde_json::Value>) {
+        let is_array = property.get("type").and_then(|t| t.as_str()) == Some("array");
+        let (min_len, max_len) = if is_array {
+            ("minItems", "maxItems")
+        } else {
+            ("minLength", "maxLength")
+        };
+
+        match self {
+            Self::Range { min, max } => {
+                if let Some(min) = min {
+                    property.insert("minimum".into(), json_number(*min));
+                }
+                if let Some(max) = max {
+                    property.insert("maximum".into(), json_number(*max));
+                }
+            }
+            Self::Length { min, max } => {
+                if let Some(min) = min {
+                    property.insert(min_len.into(), (*min).into());
+                }
+                if let Some(max) = max {
+                    property.insert(max_len.into(), (*max).into());
+                }
+            }
+            Self::Email => {
+                property.insert("format".into(), "email".into());
+            }
+            Self::Url => {
+                property.insert("format".into(), "uri".into());
+            }
+            Self::Other(_) => {}
+        }
+    }
+}
+
+fn write_doc(out: &mut String, comment: &str, doc: Option<&str>) {
+    for line in doc.iter().flat_map(|doc| doc.lines()) {
+        let _ = writeln!(out, "{comment}# {}", line.trim());
+    }
+}
+
+/// Writes the `[key]` table of `fields`, then one sub-table per nested section.
+///
+/// `comment` prefixes every line, set to `"# "` below an optional nested section.
+fn write_table(out: &mut String, comment: &str, key: &str, fields: &[FieldSchema]) {
+    let _ = writeln!(out, "{comment}[{key}]");
+
+    let (tables, values) = fields
+        .iter()
+        .partition::<Vec<_>, _>(|field| !field.fields.is_empty());
+
+    for field in values {
+        write_doc(out, comment, field.doc);
+
+        let mut notes = vec![format!("type: {}", field.ty)];
+        notes.extend(field.constraints.iter().map(Constraint::describe));
+
+        if field.required {
+            notes.push("required".to_string());
+        }
+
+        let _ = writeln!(out, "{comment}# {}", notes.join(", "));
+
+        match &field.default {
+            Some(value) => {
+                let _ = writeln!(out, "{comment}{} = {value}", field.name);
+            }
+            None if field.required => {
+                let _ = writeln!(out, "{comment}{} = {}", field.name, placeholder(field.ty));
+            }
+            None => {
+                let _ = writeln!(out, "{comment}# {} = {}", field.name, placeholder(field.ty));
+            }
+        }
+    }
+
+    for field in tables {
+        out.push('\n');
+        write_doc(out, comment, field.doc);
+
+        let required = if field.required { ", required" } else { "" };
+        let _ = writeln!(out, "{comment}# type: {}{required}", field.ty);
+
+        let comment = match comment {
+            "" if !field.required && field.default.is_none() => "# ",
+            comment => comment,
+        };
+
+        write_table(
+            out,
+            comment,
+            &format!("{key}.{}", field.name),
+            &field.fields,
+        );
+    }
+}
+
+/// Builds the JSON Schema object of `fields`, recursing into nested sections.
+#[cfg(feature = "json")]
+fn json_object(fields: &[FieldSchema]) -> serde_json::Map<String, serde_json::Value> {
+    use serde_json::{Map, Value as Json};
+
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+
+    for field in fields {
+        let mut property = if field.fields.is_empty() {
+            json_type(field.ty)
+        } else {
+            json_object(&field.fields)
+        };
+
+        if let Some(doc) = field.doc {
+            property.insert("description".into(), doc.trim().into());
+        }
+
+        if let Some(default) = field
+            .default
+            .as_ref()
+            .and_then(|d| serde_json::to_value(d).ok())
+        {
+            property.insert("default".into(), default);
+        }
+
+        for constraint in &field.constraints {
+            constraint.apply(&mut property);
+        }
+
+        if field.required {
+            required.push(Json::String(field.name.clone()));
+        }
+
+        properties.insert(field.name.clone(), Json::Object(property));
+    }
+
+    let mut schema = Map::new();
+    schema.insert("type".into(), "object".into());
+    schema.insert("properties".into(), Json::Object(properties));
+
+    if !required.is_empty() {
+        schema.insert("required".into(), Json::Array(required));
+    }
+
+    schema
+}
+
+/// Splits `Outer<Inner>` into its outer name and inner type.
+fn generic(ty: &str) -> Option<(&str, &str)> {
+    let (outer, rest) = ty.split_once('<')?;
+    let inner = rest.strip_suffix('>')?;
+    let outer = outer.rsplit("::").next().unwrap_or(outer);
+
+    Some((outer, inner))
+}
+
+fn scalar_kind(ty: &str) -> &'static str {
+    let name = ty.rsplit("::").next().unwrap_or(ty);
+
+    match name {
+        "bool" => "boolean",
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128"
+        | "usize" => "integer",
+        "f32" | "f64" => "number",
+        "String" | "str" | "&str" | "char" | "PathBuf" | "ByteConfig" | "TimeConfig" => "string",
+        _ => "object",
+    }
+}
+
+fn placeholder(ty: &str) -> String {
+    match generic(ty) {
+        Some(("Option" | "Secret" | "Box" | "Arc", inner)) => placeholder(inner),
+        Some(("Vec" | "HashSet" | "BTreeSet", _)) => "[]".to_string(),
+        Some(_) => "{}".to_string(),
+        None => match scalar_kind(ty) {
+            "boolean" => "false".to_string(),
+            "integer" => "0".to_string(),
+            "number" => "0.0".to_string(),
+            "string" => "\"\"".to_string(),
+            _ => "{}".to_string(),
+        },
+    }
+}
+
+#[cfg(feature = "json")]
+fn json_type(ty: &str) -> serde_json::Map<String, serde_json::Value> {
+    use serde_json::json;
+
+    let value = match generic(ty) {
+        Some(("Option" | "Box" | "Arc", inner)) => return json_type(inner),
+        Some(("Secret", inner)) => {
+            let mut inner = json_type(inner);
+            inner.insert("writeOnly".into(), true.into());
+            return inner;
+        }
+        Some(("Vec" | "HashSet" | "BTreeSet", inner)) => {
+            json!({ "type": "array", "items": json_type(inner) })
+        }
+        Some(("HashMap" | "BTreeMap", inner)) => {
+            let value = inner.split_once(',').map_or(inner, |(_, value)| value);
+            json!({ "type": "object", "additionalProperties": json_type(value) })
+        }
+        Some(_) => json!({ "type": "object" }),
+        None => json!({ "type": scalar_kind(ty) }),
+    };
+
+    match value {
+        serde_json::Value::Object(map) => map,
+        _ => unreachable!("json! object literal"),
+    }
+}
+
+/// Keeps whole bounds as JSON integers, e.g. `1024` instead of `1024.0`.
+#[cfg(feature = "json")]
+fn json_number(value: f64) -> serde_json::Value {
+    if value.fract() == 0.0 && value.abs() < i64::MAX as f64 {
+        (value as i64).into()
+    } else {
+        value.into()
+    }
+}
+
+#[doc(hidden)]
+pub struct DefaultProbe<T>(pub T);
+
+/// Used by the `#[config]` macro to capture defaults of serializable fields.
+#[doc(hidden)]
+pub trait SerializeDefault {
+    fn default_value(&self) -> Option<Value>;
+}
+
+impl<T: Serialize> SerializeDefault for DefaultProbe<T> {
+    fn default_value(&self) -> Option<Value> {
+        Value::try_from(&self.0).ok()
+    }
+}
+
+/// Fallback for defaults that cannot be serialized.
+#[doc(hidden)]
+pub trait NoDefault {
+    fn default_value(&self) -> Option<Value> {
+        None
+    }
+}
+
+impl<T> NoDefault for &DefaultProbe<T> {}
+
+/// Used by the `#[config]` macro to inspect the type of a field.
+#[doc(hidden)]
+pub struct TypeProbe<T>(PhantomData<T>);
+
+impl<T> TypeProbe<T> {
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+/// Marks `Secret<T>` fields, resolved on the actual type so aliases are covered.
+#[doc(hidden)]
+pub trait SecretField {
+    fn is_secret(&self) -> bool {
+        true
+    }
+}
+
+impl<T> SecretField for TypeProbe<Secret<T>> {}
+impl<T> SecretField for TypeProbe<Option<Secret<T>>> {}
+
+/// Fallback for fields that are not secrets.
+#[doc(hidden)]
+pub trait NotSecret {
+    fn is_secret(&self) -> bool {
+        false
+    }
+}
+
+impl<T> NotSecret for &TypeProbe<T> {}
+
+/// Exposes the fields of nested `#[config]` sections.
+#[doc(hidden)]
+pub trait NestedSection {
+    fn nested_fields(&self) -> Vec<FieldSchema>;
+}
+
+impl<T: ConfigItem> NestedSection for TypeProbe<T> {
+    fn nested_fields(&self) -> Vec<FieldSchema> {
+        T::schema().fields
+    }
+}
+
+impl<T: ConfigItem> NestedSection for TypeProbe<Option<T>> {
+    fn nested_fields(&self) -> Vec<FieldSchema> {
+        T::schema().fields
+    }
+}
+
+/// Fallback for fields whose type is not a `#[config]` section.
+#[doc(hidden)]
+pub trait NotNested {
+    fn nested_fields(&self) -> Vec<FieldSchema> {
+        Vec::new()
+    }
+}
+
+impl<T> NotNested for &TypeProbe<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, serde::Deserialize)]
+    struct ServerConfig;
+
+    impl ConfigItem for ServerConfig {
+        fn key() -> &'static str {
+            "services.api"
+        }
+
+        fn schema() -> SectionSchema {
+            SectionSchema {
+                key: Self::key(),
+                doc: None,
+                fields: vec![
+                    FieldSchema {
+                        name: "port".to_string(),
+                        ty: "u16",
+                        doc: Some(" Listening port."),
+                        required: false,
+                        default: Some(Value::Integer(8080)),
+                        constraints: vec![Constraint::Range {
+                            min: Some(1024.0),
+                            max: None,
+                        }],
+                        secret: false,
+                        fields: vec![],
+                    },
+                    FieldSchema {
+                        name: "host".to_string(),
+                        ty: "String",
+                        doc: None,
+                        required: true,
+                        default: None,
+                        constraints: vec![],
+                        secret: false,
+                        fields: vec![],
+                    },
+                    FieldSchema {
+                        name: "tags".to_string(),
+                        ty: "Option<Vec<String>>",
+                        doc: None,
+                        required: false,
+                        default: None,
+                        constraints: vec![Constraint::Length {
+                            min: Some(1),
+                            max: None,
+                        }],
+                        secret: false,
+                        fields: vec![],
+                    },
+                ],
+                deny_unknown_fields: true,
+            }
+        }
+    }
+
+    #[derive(Clone, serde::Deserialize)]
+    struct DbConfig;
+
+    impl ConfigItem for DbConfig {
+        fn key() -> &'static str {
+            "db"
+        }
+
+        fn schema() -> SectionSchema {
+            let field = |name: &str, ty, required, fields| FieldSchema {
+                name: name.to_string(),
+                ty,
+                doc: None,
+                required,
+                default: None,
+                constraints: vec![],
+                secret: false,
+                fields,
+            };
+
+            SectionSchema {
+                key: Self::key(),
+                doc: None,
+                fields: vec![
+                    field(
+                        "replica",
+                        "ReplicaConfig",
+                        true,
+                        vec![field("host", "String", true, vec![])],
+                    ),
+                    field("url", "String", true, vec![]),
+                    field(
+                        "backup",
+                        "Option<ReplicaConfig>",
+                        false,
+                        vec![field("host", "String", true, vec![])],
+                    ),
+                ],
+                deny_unknown_fields: false,
+            }
+        }
+    }
+
+    #[test]
+    fn test_sample_toml_nested_sections() {
+        let sample = ConfigSchema::new().section::<DbConfig>().to_sample_toml();
+
+        assert_eq!(
+            sample,
+            "[db]\n\
+             # type: String, required\n\
+             url = \"\"\n\
+             \n\
+             # type: ReplicaConfig, required\n\
+             [db.replica]\n\
+             # type: String, required\n\
+             host = \"\"\n\
+             \n\
+             # type: Option<ReplicaConfig>\n\
+             # [db.backup]\n\
+             # # type: String, required\n\
+             # host = \"\"\n"
+        );
+
+        let parsed = toml::from_str::<toml::Table>(&sample).expect("sample must be valid TOML");
+        assert_eq!(parsed["db"]["replica"]["host"].as_str(), Some(""));
+        assert!(parsed["db"].get("backup").is_none());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_schema_nested_sections() {
+        let schema = ConfigSchema::new().section::<DbConfig>().to_json_schema();
+        let schema = serde_json::from_str::<serde_json::Value>(&schema).expect("valid JSON");
+        let replica = &schema["properties"]["db"]["properties"]["replica"];
+
+        assert_eq!(replica["type"], "object");
+        assert_eq!(replica["properties"]["host"]["type"], "string");
+        assert_eq!(replica["required"], serde_json::json!(["host"]));
+    }
+
+    #[test]
+    fn test_sample_toml() {
+        let sample = ConfigSchema::new()
+            .section::<ServerConfig>()
+            .to_sample_toml();
+
+        assert_eq!(
+            sample,
+            "[services.api]\n\
+             # Listening port.\n\
+             # type: u16, range(min = 1024)\n\
+             port = 8080\n\
+             # type: String, required\n\
+             host = \"\"\n\
+             # type: Option<Vec<String>>, length(min = 1)\n\
+             # tags = []\n"
+        );
+
+        let parsed = toml::from_str::<toml::Table>(&sample).expect("sample must be valid TOML");
+        assert_eq!(parsed["services"]["api"]["port"].as_integer(), Some(8080));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_schema() {
+        let schema = ConfigSchema::new()
+            .section::<ServerConfig>()
+            .to_json_schema();
+        let schema = serde_json::from_str::<serde_json::Value>(&schema).expect("valid JSON");
+        let api = &schema["properties"]["services"]["properties"]["api"];
+
+        assert_eq!(api["required"], serde_json::json!(["host"]));
+        assert_eq!(api["additionalProperties"], false);
+        assert_eq!(api["properties"]["port"]["type"], "integer");
+        assert_eq!(api["properties"]["port"]["minimum"], 1024);
+        assert_eq!(api["properties"]["port"]["default"], 8080);
+        assert_eq!(api["properties"]["tags"]["type"], "array");
+        assert_eq!(api["properties"]["tags"]["minItems"], 1);
+    }
+}
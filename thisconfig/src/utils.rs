@@ -72,3 +72,64 @@ pub mod time_unit {
         }
     }
 }
+
+pub mod secret {
+    use crate::REDACTED;
+    use serde::{Deserialize, Serialize};
+    use std::fmt;
+
+    /// Sensitive configuration value redacted in `Debug`, `Display` and `Serialize`
+    ///
+    /// This type deserializes like the wrapped value, including interpolated values,
+    /// and only gives access to it through [`Secret::expose`].
+    ///
+    /// # Examples
+    ///
+    /// ```toml
+    /// password = "${DB_PASSWORD}"
+    /// api_key = "file:/run/secrets/api_key"
+    /// ```
+    #[derive(Clone, Default, PartialEq, Eq)]
+    pub struct Secret<T>(T);
+
+    impl<T> Secret<T> {
+        pub const fn new(value: T) -> Self {
+            Self(value)
+        }
+
+        /// Returns the wrapped value.
+        pub const fn expose(&self) -> &T {
+            &self.0
+        }
+    }
+
+    impl<T> fmt::Debug for Secret<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(REDACTED)
+        }
+    }
+
+    impl<T> fmt::Display for Secret<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(REDACTED)
+        }
+    }
+
+    impl<T> Serialize for Secret<T> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            serializer.serialize_str(REDACTED)
+        }
+    }
+
+    impl<'de, T: Deserialize<'de>> Deserialize<'de> for Secret<T> {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            T::deserialize(deserializer).map(Secret)
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! `#[config]` schema generation.
+//!
+//! Every section names the crate explicitly: building the whole workspace turns on
+//! the `axum` feature of the macros, which would otherwise point them at `axum_config`.
+
+#![cfg(feature = "macros")]
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use thisconfig::{ConfigItem, Redaction, Secret, config, schema::SectionSchema};
+
+#[test]
+fn test_config_macro_schema() {
+    fn default_port() -> u16 {
+        8080
+    }
+
+    /// HTTP server settings.
+    #[config(key = "server", crate = "thisconfig")]
+    #[derive(Debug, Clone, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    #[allow(dead_code)]
+    struct ServerConfig {
+        /// Interface to bind.
+        bind_host: String,
+        #[serde(default = "default_port")]
+        port: u16,
+        #[serde(default)]
+        workers: Vec<String>,
+        #[serde(rename = "TLS")]
+        tls: Option<bool>,
+        #[serde(skip)]
+        ignored: u8,
+    }
+
+    let SectionSchema {
+        key, doc, fields, ..
+    } = ServerConfig::schema();
+
+    assert_eq!(key, "server");
+    assert_eq!(doc, Some(" HTTP server settings."));
+
+    let summary = fields
+        .iter()
+        .map(|f| (f.name.as_str(), f.ty, f.required, f.default.clone()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        summary,
+        vec![
+            ("bindHost", "String", true, None),
+            ("port", "u16", false, Some(toml::Value::Integer(8080))),
+            (
+                "workers",
+                "Vec<String>",
+                false,
+                Some(toml::Value::Array(vec![]))
+            ),
+            ("TLS", "Option<bool>", false, None),
+        ]
+    );
+    assert_eq!(fields[0].doc, Some(" Interface to bind."));
+}
+
+#[test]
+fn test_config_macro_schema_flatten() {
+    #[config(key = "pool", crate = "thisconfig")]
+    #[derive(Debug, Clone, Deserialize)]
+    #[allow(dead_code)]
+    struct PoolConfig {
+        size: u32,
+        #[serde(default)]
+        timeout: u64,
+    }
+
+    #[derive(Debug, Clone, Deserialize)]
+    #[allow(dead_code)]
+    struct Labels {
+        team: String,
+    }
+
+    #[config(key = "db", crate = "thisconfig")]
+    #[derive(Debug, Clone, Deserialize)]
+    #[allow(dead_code)]
+    struct DbConfig {
+        url: String,
+        #[serde(flatten)]
+        pool: PoolConfig,
+        #[serde(flatten)]
+        replica_pool: Option<PoolConfig>,
+    }
+
+    #[config(key = "service", crate = "thisconfig")]
+    #[derive(Debug, Clone, Deserialize)]
+    #[allow(dead_code)]
+    struct ServiceConfig {
+        name: String,
+        #[serde(flatten)]
+        labels: Labels,
+        #[serde(flatten)]
+        extra: HashMap<String, toml::Value>,
+    }
+
+    let summary = DbConfig::schema()
+        .fields
+        .iter()
+        .map(|f| (f.name.clone(), f.required))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        summary,
+        vec![
+            ("url".to_string(), true),
+            ("size".to_string(), true),
+            ("timeout".to_string(), false),
+            ("size".to_string(), false),
+            ("timeout".to_string(), false),
+        ]
+    );
+
+    let fields = ServiceConfig::schema().fields;
+
+    assert_eq!(fields.len(), 1);
+    assert_eq!(fields[0].name, "name");
+}
+
+#[test]
+fn test_config_macro_schema_secrets() {
+    type Password = Secret<String>;
+
+    #[derive(Debug, Clone, Deserialize)]
+    #[allow(dead_code)]
+    struct MySecret<T>(T);
+
+    #[config(key = "db.replica", crate = "thisconfig")]
+    #[derive(Debug, Clone, Deserialize)]
+    #[allow(dead_code)]
+    struct ReplicaConfig {
+        host: String,
+        token: Option<Secret<String>>,
+    }
+
+    #[config(key = "db", crate = "thisconfig")]
+    #[derive(Debug, Clone, Deserialize)]
+    #[allow(dead_code)]
+    struct DbConfig {
+        password: Password,
+        dsn: thisconfig::Secret<String>,
+        label: MySecret<String>,
+        replica: ReplicaConfig,
+    }
+
+    let fields = DbConfig::schema().fields;
+    let secrets = fields.iter().map(|f| f.secret).collect::<Vec<_>>();
+
+    assert_eq!(secrets, vec![true, true, false, false]);
+    assert_eq!(fields[3].fields.len(), 2);
+    assert!(fields[3].fields[1].secret);
+
+    let redaction = Redaction::new().secrets::<DbConfig>();
+
+    assert!(redaction.is_redacted("db.password"));
+    assert!(redaction.is_redacted("db.dsn"));
+    assert!(redaction.is_redacted("db.replica.token"));
+    assert!(!redaction.is_redacted("db.label"));
+    assert!(!redaction.is_redacted("db.replica.host"));
+}
+
+#[cfg(feature = "validation")]
+#[test]
+fn test_config_macro_schema_constraints() {
+    use thisconfig::schema::Constraint;
+    use validator::Validate;
+
+    #[config(key = "limits", crate = "thisconfig")]
+    #[derive(Debug, Clone, Deserialize, Validate)]
+    #[allow(dead_code)]
+    struct LimitsConfig {
+        #[validate(range(min = -1, max = 100))]
+        rate: i32,
+        #[validate(length(min = 1, max = 8), email)]
+        contact: String,
+    }
+
+    let fields = LimitsConfig::schema().fields;
+
+    assert_eq!(
+        fields[0].constraints,
+        vec![Constraint::Range {
+            min: Some(-1.0),
+            max: Some(100.0)
+        }]
+    );
+    assert_eq!(
+        fields[1].constraints,
+        vec![
+            Constraint::Length {
+                min: Some(1),
+                max: Some(8)
+            },
+            Constraint::Email
+        ]
+    );
+}